@@ -0,0 +1,436 @@
+//====================================================================
+
+#[cfg(feature = "serde")]
+use anyhow::Context;
+
+use crate::{
+    ik::{
+        Bend, ChainRef, CollisionLayers, IkSolver, Node, NodeId, RopeSettings, Skeleton,
+        SkinSettings, SolveLimits, Spring,
+    },
+    renderer::{circles::RawInstance, polygon::BodyColors, svg::SvgDocument},
+};
+
+//====================================================================
+
+/// Data description of a creature that can be built into a [`Skeleton`]. With the
+/// `serde` feature it can be loaded from and saved to RON, JSON and TOML.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CreatureDef {
+    pub nodes: Vec<NodeDef>,
+    /// Chains referencing nodes by their index in `nodes`. Chains attach to each
+    /// other by sharing a node.
+    pub chains: Vec<ChainDef>,
+    pub style: Style,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodeDef {
+    pub radius: f32,
+    /// Rotation limits relative to the parent node in degrees.
+    pub min_angle: f32,
+    pub max_angle: f32,
+    pub pos: [f32; 2],
+    /// Springs easing the node after its parent in forward kinematic chains.
+    pub rotation_spring: Option<Spring>,
+    pub position_spring: Option<Spring>,
+}
+
+impl Default for NodeDef {
+    fn default() -> Self {
+        let node = Node::default();
+
+        Self {
+            radius: node.radius,
+            min_angle: node.min_rotation.to_degrees(),
+            max_angle: node.max_rotation.to_degrees(),
+            pos: node.pos.to_array(),
+            rotation_spring: node.rotation_spring,
+            position_spring: node.position_spring,
+        }
+    }
+}
+
+impl NodeDef {
+    pub fn to_node(&self) -> Node {
+        let mut node = Node::angles(self.radius, self.min_angle, self.max_angle);
+        node.pos = self.pos.into();
+        node.rotation_spring = self.rotation_spring;
+        node.position_spring = self.position_spring;
+        node
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChainDef {
+    Forward {
+        nodes: Vec<usize>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        layers: CollisionLayers,
+    },
+    Inverse {
+        nodes: Vec<usize>,
+        /// Starting target. Defaults to the position of the last node.
+        #[cfg_attr(feature = "serde", serde(default))]
+        target: Option<[f32; 2]>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        solver: IkSolver,
+        #[cfg_attr(feature = "serde", serde(default))]
+        limits: SolveLimits,
+        #[cfg_attr(feature = "serde", serde(default))]
+        pole: Option<[f32; 2]>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        bend: Option<Bend>,
+        /// Fastest the end of the chain follows its target, in units per second.
+        #[cfg_attr(feature = "serde", serde(default))]
+        max_speed: Option<f32>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        layers: CollisionLayers,
+    },
+    /// Verlet simulated chain hanging off its first node.
+    Rope {
+        nodes: Vec<usize>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        settings: RopeSettings,
+        #[cfg_attr(feature = "serde", serde(default))]
+        layers: CollisionLayers,
+    },
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Style {
+    pub node_color: [f32; 4],
+    pub border_color: [f32; 4],
+    pub border_radius: f32,
+    /// Draw a marker at the front of every chain node.
+    pub direction_markers: bool,
+    /// Colours of the body skin drawn around forward kinematic chains and ropes.
+    pub body_colors: BodyColors,
+    pub skin: bool,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        let instance = RawInstance::new([0., 0.], 0.).hollow();
+
+        Self {
+            node_color: instance.color,
+            border_color: instance.border_color,
+            border_radius: instance.border_radius,
+            direction_markers: true,
+            body_colors: BodyColors::default(),
+            skin: false,
+        }
+    }
+}
+
+//====================================================================
+
+pub struct Creature {
+    pub skeleton: Skeleton,
+    pub style: Style,
+    /// Skeleton handles of the definition's nodes, in definition order.
+    pub nodes: Vec<NodeId>,
+}
+
+impl CreatureDef {
+    pub fn build(&self) -> Creature {
+        let mut skeleton = Skeleton::new();
+
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|node| skeleton.add_node(node.to_node()))
+            .collect::<Vec<_>>();
+
+        let resolve = |indices: &[usize]| {
+            indices
+                .iter()
+                .filter_map(|index| {
+                    let id = nodes.get(*index).copied();
+                    if id.is_none() {
+                        log::warn!("Creature chain references missing node {}", index);
+                    }
+                    id
+                })
+                .collect::<Vec<_>>()
+        };
+
+        self.chains.iter().for_each(|chain| match chain {
+            ChainDef::Forward { nodes, layers } => {
+                let index = skeleton.add_fk(resolve(nodes));
+                skeleton.set_layers(ChainRef::Forward(index), *layers);
+            }
+            ChainDef::Inverse {
+                nodes,
+                target,
+                solver,
+                limits,
+                pole,
+                bend,
+                max_speed,
+                layers,
+            } => {
+                let index = skeleton.add_ik(resolve(nodes));
+                let ik = skeleton.get_ik(index).unwrap();
+
+                if let Some(target) = target {
+                    ik.target = (*target).into();
+                }
+                ik.solver = *solver;
+                ik.limits = *limits;
+                ik.pole = pole.map(Into::into);
+                ik.bend = *bend;
+                ik.max_speed = *max_speed;
+
+                skeleton.set_layers(ChainRef::Inverse(index), *layers);
+            }
+            ChainDef::Rope {
+                nodes,
+                settings,
+                layers,
+            } => {
+                let index = skeleton.add_rope(resolve(nodes));
+                skeleton.get_rope(index).unwrap().settings = *settings;
+                skeleton.set_layers(ChainRef::Rope(index), *layers);
+            }
+        });
+
+        skeleton.tick();
+
+        Creature {
+            skeleton,
+            style: self.style.clone(),
+            nodes,
+        }
+    }
+}
+
+impl Creature {
+    /// Replace the creature with a freshly built definition. Node positions and
+    /// rotations carry over when the node count is unchanged and inverse kinematic
+    /// targets carry over for chains present in both.
+    pub fn rebuild(&mut self, def: &CreatureDef) {
+        let mut creature = def.build();
+
+        if creature.nodes.len() == self.nodes.len() {
+            creature
+                .nodes
+                .iter()
+                .zip(self.nodes.iter())
+                .for_each(|(new, old)| {
+                    let (Some(old), Some(new)) = (
+                        self.skeleton.get_node(*old),
+                        creature.skeleton.get_node_mut(*new),
+                    ) else {
+                        return;
+                    };
+
+                    new.pos = old.pos;
+                    new.set_rotation(old.get_rotation());
+                });
+        }
+
+        let mut index = 0;
+        while let (Some(old), Some(new)) =
+            (self.skeleton.get_ik(index), creature.skeleton.get_ik(index))
+        {
+            new.target = old.target;
+            index += 1;
+        }
+
+        *self = creature;
+    }
+
+    pub fn circles(&self) -> Vec<RawInstance> {
+        let style = &self.style;
+
+        let mut circles = self
+            .nodes
+            .iter()
+            .filter_map(|id| self.skeleton.get_node(*id))
+            .map(|node| {
+                RawInstance::new(node.pos.to_array(), node.radius)
+                    .with_color(style.node_color)
+                    .with_border(style.border_radius, style.border_color)
+            })
+            .collect::<Vec<_>>();
+
+        if style.direction_markers {
+            circles.extend(
+                self.nodes
+                    .iter()
+                    .filter_map(|id| self.skeleton.get_node(*id))
+                    .map(|node| {
+                        RawInstance::new(node.get_point(node.get_rotation()).to_array(), 5.)
+                            .with_color([1., 0., 0., 1.])
+                    }),
+            );
+        }
+
+        circles
+    }
+
+    /// Export the creature as drawn, with the body skin when enabled in its style.
+    pub fn to_svg(&self) -> SvgDocument {
+        let mut document = SvgDocument::new();
+
+        if self.style.skin {
+            self.skeleton
+                .body_outlines(SkinSettings::default())
+                .iter()
+                .for_each(|points| document.add_polygon(points, self.style.body_colors.head));
+        }
+        document.add_circles(&self.circles());
+
+        document
+    }
+}
+
+//====================================================================
+
+#[cfg(feature = "serde")]
+impl CreatureDef {
+    pub fn from_ron(data: &str) -> anyhow::Result<Self> {
+        Ok(ron::from_str(data)?)
+    }
+
+    pub fn to_ron(&self) -> anyhow::Result<String> {
+        Ok(ron::ser::to_string_pretty(
+            self,
+            ron::ser::PrettyConfig::default(),
+        )?)
+    }
+
+    pub fn from_json(data: &str) -> anyhow::Result<Self> {
+        Ok(serde_json::from_str(data)?)
+    }
+
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_toml(data: &str) -> anyhow::Result<Self> {
+        Ok(toml::from_str(data)?)
+    }
+
+    pub fn to_toml(&self) -> anyhow::Result<String> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+
+    /// Load a definition, picking the format from the file extension.
+    pub fn load(path: impl AsRef<std::path::Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let data = std::fs::read_to_string(path)
+            .with_context(|| format!("Could not read creature file {}", path.display()))?;
+
+        match extension(path)? {
+            Format::Ron => Self::from_ron(&data),
+            Format::Json => Self::from_json(&data),
+            Format::Toml => Self::from_toml(&data),
+        }
+        .with_context(|| format!("Could not parse creature file {}", path.display()))
+    }
+
+    /// Save the definition, picking the format from the file extension.
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+
+        let data = match extension(path)? {
+            Format::Ron => self.to_ron(),
+            Format::Json => self.to_json(),
+            Format::Toml => self.to_toml(),
+        }?;
+
+        std::fs::write(path, data)
+            .with_context(|| format!("Could not write creature file {}", path.display()))
+    }
+}
+
+/// Polls a creature definition file for changes.
+#[cfg(feature = "serde")]
+pub struct CreatureWatcher {
+    path: std::path::PathBuf,
+    modified: Option<std::time::SystemTime>,
+    interval: std::time::Duration,
+    last_poll: Option<std::time::Instant>,
+}
+
+#[cfg(feature = "serde")]
+impl CreatureWatcher {
+    pub fn new(path: impl Into<std::path::PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            modified: None,
+            interval: std::time::Duration::from_millis(500),
+            last_poll: None,
+        }
+    }
+
+    pub fn with_interval(mut self, interval: std::time::Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    #[inline]
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+
+    /// Reload the definition if the file changed since it was last loaded. The
+    /// file is checked at most once per interval. A file that fails to parse is
+    /// reported once and retried after its next change.
+    pub fn poll(&mut self) -> Option<anyhow::Result<CreatureDef>> {
+        if self
+            .last_poll
+            .is_some_and(|last_poll| last_poll.elapsed() < self.interval)
+        {
+            return None;
+        }
+        self.last_poll = Some(std::time::Instant::now());
+
+        let modified = match std::fs::metadata(&self.path).and_then(|data| data.modified()) {
+            Ok(modified) => modified,
+            Err(e) if self.modified.is_none() => {
+                self.modified = Some(std::time::SystemTime::UNIX_EPOCH);
+                return Some(Err(e).with_context(|| {
+                    format!("Could not read creature file {}", self.path.display())
+                }));
+            }
+            Err(_) => return None,
+        };
+
+        if self.modified == Some(modified) {
+            return None;
+        }
+        self.modified = Some(modified);
+
+        Some(CreatureDef::load(&self.path))
+    }
+}
+
+#[cfg(feature = "serde")]
+enum Format {
+    Ron,
+    Json,
+    Toml,
+}
+
+#[cfg(feature = "serde")]
+fn extension(path: &std::path::Path) -> anyhow::Result<Format> {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("ron") => Ok(Format::Ron),
+        Some("json") => Ok(Format::Json),
+        Some("toml") => Ok(Format::Toml),
+        _ => anyhow::bail!("Unknown creature file format {}", path.display()),
+    }
+}
+
+//====================================================================
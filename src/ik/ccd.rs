@@ -0,0 +1,84 @@
+//====================================================================
+
+use std::cell::RefCell;
+
+use glam::{Mat2, Vec2};
+
+use super::{
+    forward,
+    solver::{SolveLimits, SolveReport, Solver},
+    wrap_angle, Node,
+};
+
+//====================================================================
+
+/// Cyclic Coordinate Descent. Rotates the chain around each joint in turn, from the
+/// end effector back to the anchor, clamping every rotation to the limits of the node
+/// it bends so constraints are respected while solving rather than after the fact.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Ccd;
+
+impl Solver for Ccd {
+    fn solve(
+        &self,
+        nodes: &[&RefCell<Node>],
+        anchor: Vec2,
+        target: Vec2,
+        limits: SolveLimits,
+    ) -> SolveReport {
+        if nodes.len() < 2 {
+            return SolveReport::new(nodes, anchor, target, 0, limits);
+        }
+
+        nodes[0].borrow_mut().pos = anchor;
+        forward(nodes);
+
+        for iteration in 1..=limits.max_iterations {
+            (0..nodes.len() - 1).rev().for_each(|index| {
+                let (joint_pos, joint_rot) = {
+                    let joint = nodes[index].borrow();
+                    (joint.pos, joint.rotation)
+                };
+                let effector = nodes.last().unwrap().borrow().pos;
+
+                let to_effector = effector - joint_pos;
+                let to_target = target - joint_pos;
+                if to_effector == Vec2::ZERO || to_target == Vec2::ZERO {
+                    return;
+                }
+
+                let desired = wrap_angle(to_target.to_angle() - to_effector.to_angle());
+
+                // Only the node directly after the joint changes its angle relative to its parent
+                let angle = {
+                    let child = nodes[index + 1].borrow();
+                    let current = wrap_angle(child.rotation - joint_rot);
+                    let clamped =
+                        wrap_angle(current + desired).clamp(child.min_rotation, child.max_rotation);
+
+                    clamped - current
+                };
+
+                if angle == 0. {
+                    return;
+                }
+
+                let rotation = Mat2::from_angle(angle);
+                nodes[index + 1..].iter().for_each(|node| {
+                    let mut node = node.borrow_mut();
+                    node.pos = joint_pos + rotation * (node.pos - joint_pos);
+                    node.rotation += angle;
+                });
+            });
+
+            let report = SolveReport::new(nodes, anchor, target, iteration, limits);
+            if report.reached {
+                return report;
+            }
+        }
+
+        SolveReport::new(nodes, anchor, target, limits.max_iterations, limits)
+    }
+}
+
+//====================================================================
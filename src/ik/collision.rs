@@ -0,0 +1,296 @@
+//====================================================================
+
+use std::collections::{HashMap, HashSet};
+
+use glam::Vec2;
+
+use crate::world::World;
+
+use super::{NodeId, Skeleton};
+
+//====================================================================
+
+/// Bit masks deciding which chains collide. Two nodes collide when each one's
+/// `member` bits overlap the other's `mask`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CollisionLayers {
+    pub member: u32,
+    pub mask: u32,
+}
+
+impl Default for CollisionLayers {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl CollisionLayers {
+    pub const ALL: Self = Self::new(u32::MAX, u32::MAX);
+    pub const NONE: Self = Self::new(0, 0);
+
+    pub const fn new(member: u32, mask: u32) -> Self {
+        Self { member, mask }
+    }
+
+    #[inline]
+    pub fn interacts(&self, other: &CollisionLayers) -> bool {
+        self.member & other.mask != 0 && other.member & self.mask != 0
+    }
+
+    #[inline]
+    fn union(self, other: CollisionLayers) -> Self {
+        Self::new(self.member | other.member, self.mask | other.mask)
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CollisionSettings {
+    /// Nodes of the same skeleton this many links apart or closer never collide.
+    /// Linked nodes always overlap, since the link length is the parent's radius.
+    pub skip_adjacent: usize,
+    /// Resolution passes. Later passes fix overlaps caused by earlier pushes.
+    pub iterations: usize,
+    /// Broad phase grid cell size. Defaults to the largest node diameter.
+    pub cell_size: Option<f32>,
+}
+
+impl Default for CollisionSettings {
+    fn default() -> Self {
+        Self {
+            skip_adjacent: 2,
+            iterations: 2,
+            cell_size: None,
+        }
+    }
+}
+
+//====================================================================
+
+struct Body {
+    skeleton: usize,
+    id: NodeId,
+    pos: Vec2,
+    radius: f32,
+    layers: CollisionLayers,
+}
+
+/// Push overlapping nodes apart, both within and between skeletons. Run before
+/// updating the skeletons so chains re-attach the pushed nodes. Returns the number
+/// of overlaps resolved.
+pub fn collide(skeletons: &mut [&mut Skeleton], settings: &CollisionSettings) -> usize {
+    let mut bodies = Vec::new();
+    let mut neighbours = HashSet::new();
+
+    skeletons
+        .iter()
+        .enumerate()
+        .for_each(|(skeleton_index, skeleton)| {
+            let layers = skeleton.node_layers();
+
+            let mut ids = skeleton.nodes.keys().copied().collect::<Vec<_>>();
+            ids.sort_unstable();
+
+            bodies.extend(ids.into_iter().map(|id| {
+                let node = skeleton.nodes[&id].borrow();
+                Body {
+                    skeleton: skeleton_index,
+                    id,
+                    pos: node.pos,
+                    radius: node.radius,
+                    layers: layers.get(&id).copied().unwrap_or_default(),
+                }
+            }));
+
+            neighbours.extend(
+                skeleton
+                    .neighbours(settings.skip_adjacent)
+                    .into_iter()
+                    .map(|(a, b)| (skeleton_index, a, b)),
+            );
+        });
+
+    let max_radius = bodies.iter().map(|body| body.radius).fold(0., f32::max);
+    let cell_size = settings
+        .cell_size
+        .unwrap_or(max_radius * 2.)
+        .max(max_radius * 2.)
+        .max(1.);
+
+    let mut count = 0;
+
+    (0..settings.iterations).for_each(|_| {
+        let grid = Grid::new(&bodies, cell_size);
+
+        (0..bodies.len()).for_each(|first| {
+            grid.nearby(bodies[first].pos)
+                .filter(|second| *second > first)
+                .for_each(|second| {
+                    let (a, b) = (&bodies[first], &bodies[second]);
+
+                    if !a.layers.interacts(&b.layers)
+                        || (a.skeleton == b.skeleton
+                            && neighbours.contains(&(a.skeleton, a.id.min(b.id), a.id.max(b.id))))
+                    {
+                        return;
+                    }
+
+                    let delta = b.pos - a.pos;
+                    let overlap = a.radius + b.radius - delta.length();
+                    if overlap <= 0. {
+                        return;
+                    }
+
+                    let push = delta.normalize_or(Vec2::X) * overlap / 2.;
+                    bodies[first].pos -= push;
+                    bodies[second].pos += push;
+                    count += 1;
+                });
+        });
+    });
+
+    bodies.iter().for_each(|body| {
+        if let Some(node) = skeletons[body.skeleton].get_node_mut(body.id) {
+            node.pos = body.pos;
+        }
+    });
+
+    count
+}
+
+//--------------------------------------------------
+
+/// Uniform grid broad phase. Cells are at least a node diameter wide, so overlapping
+/// nodes always sit in the same or neighbouring cells.
+struct Grid {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<usize>>,
+}
+
+impl Grid {
+    fn new(bodies: &[Body], cell_size: f32) -> Self {
+        let mut grid = Self {
+            cell_size,
+            cells: HashMap::new(),
+        };
+
+        bodies.iter().enumerate().for_each(|(index, body)| {
+            grid.cells
+                .entry(grid.cell(body.pos))
+                .or_default()
+                .push(index);
+        });
+
+        grid
+    }
+
+    #[inline]
+    fn cell(&self, pos: Vec2) -> (i32, i32) {
+        let cell = (pos / self.cell_size).floor();
+        (cell.x as i32, cell.y as i32)
+    }
+
+    fn nearby(&self, pos: Vec2) -> impl Iterator<Item = usize> + '_ {
+        let (x, y) = self.cell(pos);
+
+        (-1..=1)
+            .flat_map(move |dx| (-1..=1).map(move |dy| (x + dx, y + dy)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+    }
+}
+
+//====================================================================
+
+impl Skeleton {
+    /// Resolve overlaps between nodes of this skeleton only.
+    pub fn collide(&mut self, settings: &CollisionSettings) -> usize {
+        collide(&mut [self], settings)
+    }
+
+    /// Push every node out of world geometry, treating nodes as circles. Returns the
+    /// number of nodes moved.
+    pub fn collide_world(&mut self, world: &World) -> usize {
+        self.nodes
+            .values_mut()
+            .map(|node| {
+                let node = node.get_mut();
+                let (pos, moved) = world.push_out(node.pos, node.radius);
+
+                node.pos = pos;
+                moved
+            })
+            .filter(|moved| *moved)
+            .count()
+    }
+
+    /// Mark the report of every inverse kinematic chain whose target is blocked by
+    /// world geometry as seen from the chain's first node.
+    pub fn check_occlusion(&mut self, world: &World) {
+        self.inverse_kinematics.iter_mut().for_each(|chain| {
+            let anchor = chain
+                .nodes
+                .first()
+                .and_then(|id| self.nodes.get(id))
+                .map(|node| node.borrow().pos);
+
+            chain.report.occluded =
+                anchor.is_some_and(|anchor| world.occluded(anchor, chain.target));
+        });
+    }
+
+    /// Layers of every node, combining the layers of every chain it belongs to.
+    fn node_layers(&self) -> HashMap<NodeId, CollisionLayers> {
+        let mut layers = HashMap::<NodeId, CollisionLayers>::new();
+
+        self.chains().for_each(|(chain, nodes)| {
+            let chain_layers = self.layers(chain);
+
+            nodes.iter().for_each(|id| {
+                layers
+                    .entry(*id)
+                    .and_modify(|layers| *layers = layers.union(chain_layers))
+                    .or_insert(chain_layers);
+            });
+        });
+
+        layers
+    }
+
+    /// Pairs of nodes at most `links` links apart, smallest id first.
+    fn neighbours(&self, links: usize) -> HashSet<(NodeId, NodeId)> {
+        let mut linked = HashMap::<NodeId, Vec<NodeId>>::new();
+        self.chains().for_each(|(_, nodes)| {
+            nodes.windows(2).for_each(|pair| {
+                linked.entry(pair[0]).or_default().push(pair[1]);
+                linked.entry(pair[1]).or_default().push(pair[0]);
+            });
+        });
+
+        let mut pairs = HashSet::new();
+
+        linked.keys().for_each(|start| {
+            let mut visited = HashSet::from([*start]);
+            let mut frontier = vec![*start];
+
+            (0..links).for_each(|_| {
+                frontier = frontier
+                    .iter()
+                    .flat_map(|id| linked[id].iter().copied())
+                    .filter(|id| visited.insert(*id))
+                    .collect();
+            });
+
+            visited.into_iter().for_each(|id| {
+                pairs.insert((id.min(*start), id.max(*start)));
+            });
+        });
+
+        pairs
+    }
+}
+
+//====================================================================
@@ -0,0 +1,61 @@
+//====================================================================
+
+use std::cell::RefCell;
+
+use glam::Vec2;
+
+use super::{
+    forward,
+    solver::{SolveLimits, SolveReport, Solver},
+    Node,
+};
+
+//====================================================================
+
+/// Forward And Backward Reaching Inverse Kinematics.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Fabrik;
+
+impl Solver for Fabrik {
+    fn solve(
+        &self,
+        nodes: &[&RefCell<Node>],
+        anchor: Vec2,
+        target: Vec2,
+        limits: SolveLimits,
+    ) -> SolveReport {
+        if nodes.len() < 3 {
+            return SolveReport::new(nodes, anchor, target, 0, limits);
+        }
+
+        let initial_rot = nodes[0].borrow().rotation;
+
+        for iteration in 1..=limits.max_iterations {
+            nodes.last().unwrap().borrow_mut().pos = target;
+
+            (0..nodes.len() - 1).rev().for_each(|index| {
+                let first = nodes[index + 1].borrow();
+                let mut second = nodes[index].borrow_mut();
+
+                second.reach_towards(&first);
+            });
+
+            {
+                let mut node = nodes[0].borrow_mut();
+                node.pos = anchor;
+                node.rotation = initial_rot;
+            }
+
+            forward(nodes);
+
+            let report = SolveReport::new(nodes, anchor, target, iteration, limits);
+            if report.reached {
+                return report;
+            }
+        }
+
+        SolveReport::new(nodes, anchor, target, limits.max_iterations, limits)
+    }
+}
+
+//====================================================================
@@ -0,0 +1,151 @@
+//====================================================================
+
+use std::cell::RefCell;
+
+use glam::{Mat2, Vec2};
+
+use super::{
+    forward,
+    solver::{SolveLimits, SolveReport, Solver},
+    wrap_angle, Node,
+};
+
+//====================================================================
+
+/// Damped least squares solve on the relative joint angles of the chain. The rest
+/// pose objective is projected into the null space of the jacobian so it never
+/// fights the primary objective of reaching the target.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Jacobian {
+    /// Damping factor of the least squares solve. Higher values are more stable near
+    /// singular (fully stretched) poses at the cost of slower convergence.
+    pub damping: f32,
+    /// Maximum distance the end effector is pushed towards the target each iteration.
+    pub step: f32,
+    /// Strength of the secondary objective pulling joints towards their rest pose,
+    /// the middle of each node's rotation limits. Zero disables it. Damping lets some
+    /// of it leak into the primary objective so it should be kept small.
+    pub rest_weight: f32,
+}
+
+impl Default for Jacobian {
+    fn default() -> Self {
+        Self {
+            damping: 10.,
+            step: 20.,
+            rest_weight: 0.,
+        }
+    }
+}
+
+//====================================================================
+
+impl Solver for Jacobian {
+    fn solve(
+        &self,
+        nodes: &[&RefCell<Node>],
+        anchor: Vec2,
+        target: Vec2,
+        limits: SolveLimits,
+    ) -> SolveReport {
+        if nodes.len() < 2 {
+            return SolveReport::new(nodes, anchor, target, 0, limits);
+        }
+
+        nodes[0].borrow_mut().pos = anchor;
+        forward(nodes);
+
+        let mut angles = nodes
+            .windows(2)
+            .map(|pair| wrap_angle(pair[1].borrow().rotation - pair[0].borrow().rotation))
+            .collect::<Vec<_>>();
+
+        let rest = nodes[1..]
+            .iter()
+            .map(|node| {
+                let node = node.borrow();
+                (node.min_rotation + node.max_rotation) / 2.
+            })
+            .collect::<Vec<_>>();
+
+        for iteration in 0..limits.max_iterations {
+            let effector = nodes.last().unwrap().borrow().pos;
+
+            let error = target - effector;
+            if error.length() <= limits.tolerance {
+                return SolveReport::new(nodes, anchor, target, iteration, limits);
+            }
+            let error = error.clamp_length_max(self.step);
+
+            // Each column is the end effector velocity from rotating around a joint
+            let columns = nodes[..nodes.len() - 1]
+                .iter()
+                .map(|node| (effector - node.borrow().pos).perp())
+                .collect::<Vec<_>>();
+
+            let jjt = columns.iter().fold(Mat2::ZERO, |acc, column| {
+                acc + Mat2::from_cols(*column * column.x, *column * column.y)
+            });
+            let damped = jjt + Mat2::IDENTITY * self.damping * self.damping;
+
+            if damped.determinant().abs() <= f32::EPSILON {
+                return SolveReport::new(nodes, anchor, target, iteration, limits);
+            }
+            let inverse = damped.inverse();
+
+            // Pseudo inverse applied to a task space vector
+            let pseudo_inverse = |vector: Vec2| {
+                let solved = inverse * vector;
+                columns
+                    .iter()
+                    .map(|column| column.dot(solved))
+                    .collect::<Vec<_>>()
+            };
+
+            let mut delta = pseudo_inverse(error);
+
+            if self.rest_weight > 0. {
+                let secondary = angles
+                    .iter()
+                    .zip(rest.iter())
+                    .map(|(angle, rest)| (rest - angle) * self.rest_weight)
+                    .collect::<Vec<_>>();
+
+                let task = columns
+                    .iter()
+                    .zip(secondary.iter())
+                    .fold(Vec2::ZERO, |acc, (column, angle)| acc + *column * *angle);
+
+                delta
+                    .iter_mut()
+                    .zip(secondary.iter().zip(pseudo_inverse(task)))
+                    .for_each(|(delta, (secondary, projected))| *delta += secondary - projected);
+            }
+
+            angles
+                .iter_mut()
+                .zip(delta)
+                .zip(nodes[1..].iter())
+                .for_each(|((angle, delta), node)| {
+                    let node = node.borrow();
+                    *angle = (*angle + delta).clamp(node.min_rotation, node.max_rotation);
+                });
+
+            nodes
+                .windows(2)
+                .zip(angles.iter())
+                .for_each(|(pair, angle)| {
+                    let parent = pair[0].borrow();
+                    let mut node = pair[1].borrow_mut();
+
+                    node.rotation = parent.rotation + angle;
+                    node.pos = parent.pos - Vec2::from_angle(node.rotation) * parent.radius;
+                });
+        }
+
+        SolveReport::new(nodes, anchor, target, limits.max_iterations, limits)
+    }
+}
+
+//====================================================================
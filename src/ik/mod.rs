@@ -0,0 +1,1347 @@
+//====================================================================
+
+use core::f32;
+use std::{
+    cell::{Ref, RefCell},
+    collections::HashMap,
+    f32::consts::{FRAC_PI_2, PI, TAU},
+};
+
+use glam::{vec2, Vec2};
+
+use crate::{
+    renderer::{
+        circles::RawInstance,
+        debug::DebugDraw,
+        polygon::{BodyColors, RawVertex},
+        svg::SvgDocument,
+    },
+    world::World,
+};
+
+mod ccd;
+mod collision;
+mod fabrik;
+mod jacobian;
+mod pole;
+mod rope;
+mod skin;
+mod solver;
+mod spring;
+mod two_bone;
+
+pub use ccd::Ccd;
+pub use collision::{collide, CollisionLayers, CollisionSettings};
+pub use fabrik::Fabrik;
+pub use jacobian::Jacobian;
+pub use pole::Bend;
+pub use rope::{Rope, RopeChain, RopeSettings};
+pub use skin::{body_mesh, body_outline, SkinSettings};
+pub use solver::{chain_length, IkSolver, SolveLimits, SolveReport, Solver};
+pub use spring::Spring;
+pub use two_bone::TwoBone;
+
+//====================================================================
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Node {
+    pub radius: f32,
+
+    pub pos: Vec2,
+    rotation: f32,
+    pub max_rotation: f32,
+    pub min_rotation: f32,
+
+    /// Eases rotation towards the parent when following with a time step.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rotation_spring: Option<Spring>,
+    /// Eases position towards the attached point when following with a time step,
+    /// letting the distance to the parent stretch.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub position_spring: Option<Spring>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    angular_velocity: f32,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    velocity: Vec2,
+}
+
+impl Node {
+    const DEFAULT_ANGLE: f32 = 0.6981;
+
+    pub fn new(radius: f32) -> Self {
+        Self {
+            radius,
+            ..Default::default()
+        }
+    }
+
+    pub fn locked(radius: f32, rotation: f32) -> Self {
+        Self {
+            radius,
+            max_rotation: rotation.to_radians(),
+            min_rotation: rotation.to_radians(),
+            ..Default::default()
+        }
+    }
+
+    pub fn unlocked(radius: f32) -> Self {
+        Self {
+            radius,
+            max_rotation: TAU,
+            min_rotation: -TAU,
+            ..Default::default()
+        }
+    }
+
+    pub fn angle(radius: f32, angle: f32) -> Self {
+        Self {
+            radius,
+            max_rotation: angle.to_radians(),
+            min_rotation: angle.to_radians(),
+            ..Default::default()
+        }
+    }
+
+    pub fn angles(radius: f32, min: f32, max: f32) -> Self {
+        Self {
+            radius,
+            max_rotation: max.to_radians(),
+            min_rotation: min.to_radians(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_rotation_spring(mut self, spring: Spring) -> Self {
+        self.rotation_spring = Some(spring);
+        self
+    }
+
+    pub fn with_position_spring(mut self, spring: Spring) -> Self {
+        self.position_spring = Some(spring);
+        self
+    }
+
+    pub fn set_rotation(&mut self, rotation: f32) {
+        self.rotation = rotation;
+    }
+
+    #[inline]
+    pub fn get_rotation(&self) -> f32 {
+        self.rotation
+    }
+
+    pub fn attach_rot(&mut self, parent: &Node) {
+        let vector = parent.pos - self.pos;
+        self.set_rotation(vector.to_angle());
+
+        let rotation_diff = (self.rotation - parent.rotation + TAU + PI) % TAU - PI;
+        let rotation_diff = rotation_diff.clamp(self.min_rotation, self.max_rotation);
+        self.rotation = parent.rotation + rotation_diff;
+
+        let scaled = Vec2::from_angle(self.rotation) * parent.radius;
+        self.pos = parent.pos - scaled;
+    }
+
+    pub fn attach(&mut self, parent: &Node) {
+        let vector = parent.pos - self.pos;
+        self.set_rotation(vector.to_angle());
+
+        let scaled = Vec2::from_angle(self.rotation) * parent.radius;
+        self.pos = parent.pos - scaled;
+    }
+
+    /// Move to sit this node's radius away from a child, as in the backward pass of
+    /// FABRIK. Links are measured by the parent's radius, the same as when attaching.
+    pub fn reach_towards(&mut self, child: &Node) {
+        let direction = (self.pos - child.pos).normalize_or(Vec2::from_angle(child.rotation));
+        self.pos = child.pos + direction * self.radius;
+    }
+
+    /// Rotation relative to the parent, the value limited by `min_rotation` and `max_rotation`.
+    #[inline]
+    pub fn relative_rotation(&self, parent: &Node) -> f32 {
+        wrap_angle(self.rotation - parent.rotation)
+    }
+
+    /// Whether the node is held at one of its rotation limits.
+    pub fn is_pinned(&self, parent: &Node) -> bool {
+        const EPSILON: f32 = 0.001;
+
+        let relative = self.relative_rotation(parent);
+        relative <= self.min_rotation + EPSILON || relative >= self.max_rotation - EPSILON
+    }
+
+    pub fn get_point(&self, angle: f32) -> Vec2 {
+        let x = self.radius * angle.cos() + self.pos.x;
+        let y = self.radius * angle.sin() + self.pos.y;
+
+        vec2(x, y)
+    }
+}
+
+impl Default for Node {
+    fn default() -> Self {
+        Self {
+            radius: 80.,
+            pos: Vec2::ZERO,
+            rotation: 0.,
+            max_rotation: Self::DEFAULT_ANGLE,
+            min_rotation: -Self::DEFAULT_ANGLE,
+            rotation_spring: None,
+            position_spring: None,
+            angular_velocity: 0.,
+            velocity: Vec2::ZERO,
+        }
+    }
+}
+
+//====================================================================
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ForwardKinematic {
+    pub nodes: Vec<Node>,
+}
+
+impl ForwardKinematic {
+    pub fn tick(&mut self) {
+        if self.nodes.len() < 2 {
+            return;
+        }
+
+        (1..self.nodes.len()).for_each(|index| {
+            let (first, second) = self.nodes.split_at_mut(index);
+
+            let first = first.last().unwrap();
+            let second = &mut second[0];
+
+            second.attach_rot(first);
+        })
+    }
+
+    pub fn attach(&mut self, root: &Node) {
+        if self.nodes.is_empty() {
+            return;
+        }
+
+        self.nodes[0].attach_rot(root);
+        self.tick();
+    }
+
+    /// Same as [`ForwardKinematic::tick`], integrating node springs over `dt`.
+    pub fn update(&mut self, dt: f32) {
+        (1..self.nodes.len()).for_each(|index| {
+            let (first, second) = self.nodes.split_at_mut(index);
+            second[0].follow(first.last().unwrap(), dt);
+        });
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InverseKinematic {
+    pub nodes: Vec<RefCell<Node>>,
+    pub anchor: Vec2,
+    pub target: Vec2,
+    pub limits: SolveLimits,
+    pub solver: IkSolver,
+    /// World point the middle joints bend towards.
+    pub pole: Option<Vec2>,
+    /// Bend direction used when there is no pole target.
+    pub bend: Option<Bend>,
+    /// Fastest the end effector can move towards the target in units per second
+    /// when updated with a time step. Unlimited when `None`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub max_speed: Option<f32>,
+}
+
+impl InverseKinematic {
+    pub fn new(target: Vec2, anchor: Vec2) -> Self {
+        Self {
+            nodes: Vec::new(),
+            anchor,
+            target,
+            limits: SolveLimits::default(),
+            solver: IkSolver::default(),
+            pole: None,
+            bend: None,
+            max_speed: None,
+        }
+    }
+
+    pub fn with_max_speed(mut self, max_speed: f32) -> Self {
+        self.max_speed = Some(max_speed);
+        self
+    }
+
+    pub fn with_pole(mut self, pole: Vec2) -> Self {
+        self.pole = Some(pole);
+        self
+    }
+
+    pub fn with_bend(mut self, bend: Bend) -> Self {
+        self.bend = Some(bend);
+        self
+    }
+
+    pub fn with_solver(mut self, solver: IkSolver) -> Self {
+        self.solver = solver;
+        self
+    }
+
+    pub fn with_nodes<T: IntoIterator<Item = Node>>(mut self, nodes: T) -> Self {
+        self.add_nodes(nodes);
+        self
+    }
+
+    pub fn add_nodes<T: IntoIterator<Item = Node>>(&mut self, nodes: T) {
+        nodes
+            .into_iter()
+            .for_each(|node| self.nodes.push(RefCell::new(node)));
+    }
+
+    pub fn circles(&self) -> Vec<RawInstance> {
+        self.nodes
+            .iter()
+            .flat_map(|node| {
+                let node = node.borrow();
+                [
+                    RawInstance::new(node.pos.to_array(), node.radius).hollow(),
+                    RawInstance::new(node.get_point(node.rotation).to_array(), 5.)
+                        .with_color([1., 0., 0., 1.]),
+                ]
+            })
+            .collect()
+    }
+
+    /// Draw bones, node directions, the target and the pole.
+    pub fn debug_draw(&self, draw: &mut DebugDraw) {
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|node| node.borrow())
+            .collect::<Vec<_>>();
+        let nodes = nodes.iter().map(|node| &**node).collect::<Vec<_>>();
+
+        let reached = nodes
+            .last()
+            .is_some_and(|node| node.pos.distance(self.target) <= self.limits.tolerance);
+
+        debug_draw_chain(draw, &nodes);
+        debug_draw_target(draw, &nodes, self.target, reached, self.pole);
+    }
+
+    /// Draw the rotation range every node is allowed relative to its parent.
+    pub fn debug_draw_limits(&self, draw: &mut DebugDraw) {
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|node| node.borrow())
+            .collect::<Vec<_>>();
+        debug_draw_limits(draw, &nodes.iter().map(|node| &**node).collect::<Vec<_>>());
+    }
+
+    pub fn solve(&mut self) -> SolveReport {
+        self.solve_with(&self.solver)
+    }
+
+    /// Solve towards the target, moving the end effector no faster than `max_speed`.
+    pub fn update(&mut self, dt: f32) -> SolveReport {
+        let end = self.nodes.last().map(|node| node.borrow().pos);
+        let target = self.target;
+
+        self.target = limit_target(end, target, self.max_speed, dt);
+        let report = self.solve();
+        self.target = target;
+
+        report
+    }
+
+    /// Same as [`InverseKinematic::solve`], also reporting whether world geometry
+    /// blocks the way from the anchor to the target.
+    pub fn solve_in(&mut self, world: &World) -> SolveReport {
+        let mut report = self.solve();
+        report.occluded = world.occluded(self.anchor, self.target);
+        report
+    }
+
+    pub fn solve_with(&self, solver: &dyn Solver) -> SolveReport {
+        let nodes = self.nodes.iter().collect::<Vec<_>>();
+        solve_chain(
+            solver,
+            &nodes,
+            self.anchor,
+            self.target,
+            self.limits,
+            self.pole,
+            self.bend,
+        )
+    }
+
+    pub fn fabrik(&mut self) -> SolveReport {
+        self.solve_with(&Fabrik)
+    }
+
+    pub fn ccd(&mut self) -> SolveReport {
+        self.solve_with(&Ccd)
+    }
+
+    pub fn jacobian(&mut self, jacobian: Jacobian) -> SolveReport {
+        self.solve_with(&jacobian)
+    }
+
+    pub fn two_bone(&mut self) -> SolveReport {
+        self.solve_with(&TwoBone)
+    }
+}
+
+//====================================================================
+
+const DEBUG_BONE_COLOR: [f32; 4] = [0.5, 0.5, 0.5, 1.];
+const DEBUG_DIRECTION_COLOR: [f32; 4] = [0.2, 0.4, 1., 1.];
+const DEBUG_REACHED_COLOR: [f32; 4] = [0., 0.7, 0., 1.];
+const DEBUG_UNREACHED_COLOR: [f32; 4] = [1., 0.5, 0., 1.];
+const DEBUG_POLE_COLOR: [f32; 4] = [0.8, 0., 0.8, 1.];
+
+fn debug_draw_chain(draw: &mut DebugDraw, nodes: &[&Node]) {
+    nodes.windows(2).for_each(|pair| {
+        draw.line(pair[0].pos, pair[1].pos, 2., DEBUG_BONE_COLOR);
+    });
+
+    nodes.iter().for_each(|node| {
+        draw.arrow(
+            node.pos,
+            node.get_point(node.get_rotation()),
+            2.,
+            DEBUG_DIRECTION_COLOR,
+        );
+    });
+}
+
+fn debug_draw_target(
+    draw: &mut DebugDraw,
+    nodes: &[&Node],
+    target: Vec2,
+    reached: bool,
+    pole: Option<Vec2>,
+) {
+    let color = match reached {
+        true => DEBUG_REACHED_COLOR,
+        false => DEBUG_UNREACHED_COLOR,
+    };
+
+    if let Some(last) = nodes.last() {
+        draw.line(last.pos, target, 1., color);
+    }
+    draw.cross(target, 16., 3., color);
+
+    if let Some(pole) = pole {
+        if let Some(middle) = nodes.get(nodes.len() / 2) {
+            draw.arrow(middle.pos, pole, 1., DEBUG_POLE_COLOR);
+        }
+        draw.cross(pole, 12., 2., DEBUG_POLE_COLOR);
+    }
+}
+
+const DEBUG_LIMIT_COLOR: [f32; 4] = [0.2, 0.6, 1., 0.25];
+const DEBUG_PINNED_COLOR: [f32; 4] = [1., 0.2, 0.1, 0.4];
+
+/// Draw each node's allowed range as a wedge around its parent, covering every
+/// position the node can take. Wedges of nodes held at a limit are highlighted.
+fn debug_draw_limits(draw: &mut DebugDraw, nodes: &[&Node]) {
+    nodes.windows(2).for_each(|pair| {
+        let (parent, node) = (pair[0], pair[1]);
+
+        let (color, edge_color) = match node.is_pinned(parent) {
+            true => (DEBUG_PINNED_COLOR, [1., 0.2, 0.1, 1.]),
+            false => (DEBUG_LIMIT_COLOR, [0.2, 0.6, 1., 1.]),
+        };
+
+        // Nodes sit behind their parent, opposite the direction they face
+        let start = parent.rotation + node.min_rotation + PI;
+        let sweep = node.max_rotation - node.min_rotation;
+
+        draw.wedge(parent.pos, parent.radius, start, sweep, color);
+        draw.line(
+            parent.pos,
+            parent.pos + Vec2::from_angle(start) * parent.radius,
+            1.,
+            edge_color,
+        );
+        draw.line(
+            parent.pos,
+            parent.pos + Vec2::from_angle(start + sweep) * parent.radius,
+            1.,
+            edge_color,
+        );
+        draw.line(parent.pos, node.pos, 2., edge_color);
+    });
+}
+
+/// Move a goal from the current end effector towards the target, no further than
+/// `max_speed` allows within `dt`.
+fn limit_target(end: Option<Vec2>, target: Vec2, max_speed: Option<f32>, dt: f32) -> Vec2 {
+    match (end, max_speed) {
+        (Some(end), Some(max_speed)) => end + (target - end).clamp_length_max(max_speed * dt),
+        _ => target,
+    }
+}
+
+#[inline]
+fn wrap_angle(angle: f32) -> f32 {
+    (angle + PI).rem_euclid(TAU) - PI
+}
+
+/// Solve a single chain, steering its bend direction before and after solving.
+fn solve_chain(
+    solver: &dyn Solver,
+    nodes: &[&RefCell<Node>],
+    anchor: Vec2,
+    target: Vec2,
+    limits: SolveLimits,
+    pole: Option<Vec2>,
+    bend: Option<Bend>,
+) -> SolveReport {
+    pole::apply(nodes, pole, bend);
+    let report = solver.solve_towards(nodes, anchor, target, pole, limits);
+
+    if pole.is_none() && bend.is_none() {
+        return report;
+    }
+
+    pole::apply(nodes, pole, bend);
+    SolveReport::new(nodes, anchor, target, report.iterations, limits)
+}
+
+/// Drop ids repeated back to back. A node linked to itself would be borrowed
+/// twice while updating its chain.
+fn without_repeats(nodes: Vec<NodeId>) -> Vec<NodeId> {
+    nodes.into_iter().fold(Vec::new(), |mut ids, id| {
+        match ids.last() == Some(&id) {
+            true => log::warn!("Skeleton chain repeats node {} in a row", id),
+            false => ids.push(id),
+        }
+        ids
+    })
+}
+
+fn forward(nodes: &[&RefCell<Node>]) {
+    nodes.windows(2).for_each(|pair| {
+        let first = pair[0].borrow();
+        let mut second = pair[1].borrow_mut();
+
+        second.attach_rot(&first);
+    });
+}
+
+//====================================================================
+
+pub fn triangle_list(nodes: &[Node]) -> Vec<[f32; 2]> {
+    nodes
+        .iter()
+        .flat_map(|node| {
+            let node_right = node.get_point(node.get_rotation() - FRAC_PI_2).to_array();
+            let node_left = node.get_point(node.get_rotation() + FRAC_PI_2).to_array();
+            vec![node_right, node_left]
+        })
+        .collect()
+}
+
+//====================================================================
+
+pub type NodeId = usize;
+
+/// Snapshot of node positions and rotations, used to interpolate between
+/// simulation steps when rendering.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Pose {
+    nodes: Vec<(NodeId, Vec2, f32)>,
+}
+
+impl Pose {
+    /// Blend towards another pose of the same skeleton, taking the shortest way
+    /// around for rotations.
+    pub fn lerp(&self, other: &Pose, amount: f32) -> Pose {
+        let other = other
+            .nodes
+            .iter()
+            .map(|(id, pos, rotation)| (*id, (*pos, *rotation)))
+            .collect::<HashMap<_, _>>();
+
+        Pose {
+            nodes: self
+                .nodes
+                .iter()
+                .map(|(id, pos, rotation)| match other.get(id) {
+                    Some((other_pos, other_rotation)) => (
+                        *id,
+                        pos.lerp(*other_pos, amount),
+                        rotation + wrap_angle(other_rotation - rotation) * amount,
+                    ),
+                    None => (*id, *pos, *rotation),
+                })
+                .collect(),
+        }
+    }
+}
+
+pub struct IkChain {
+    pub nodes: Vec<NodeId>,
+    pub target: Vec2,
+    pub limits: SolveLimits,
+    /// Last result of solving the chain.
+    pub report: SolveReport,
+    /// World point the middle joints bend towards. Ignored for chains solved as part of a tree.
+    pub pole: Option<Vec2>,
+    /// Bend direction used when there is no pole target. Ignored for chains solved as part of a tree.
+    pub bend: Option<Bend>,
+    /// Solver used when the chain is solved on its own. Chains branching into a tree
+    /// are always solved together using FABRIK.
+    pub solver: IkSolver,
+    /// Fastest the end effector can move towards the target in units per second
+    /// when the skeleton is updated with a time step. Unlimited when `None`.
+    pub max_speed: Option<f32>,
+}
+
+enum Chain {
+    Forward(usize),
+    Rope(usize),
+    /// Root inverse kinematic chain followed by every chain branching off it,
+    /// solved together as a single tree.
+    Inverse(Vec<usize>),
+}
+
+/// Chain of a [`Skeleton`] by kind and the index returned when adding it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChainRef {
+    Forward(usize),
+    Rope(usize),
+    Inverse(usize),
+}
+
+/// Node graph owning every node by handle. Chains only reference nodes so
+/// several of them can share a joint, e.g. a leg branching off a spine. Ids
+/// chains reference that have no node are skipped.
+#[derive(Default)]
+pub struct Skeleton {
+    next_id: NodeId,
+    nodes: HashMap<NodeId, RefCell<Node>>,
+
+    forward_kinematics: Vec<Vec<NodeId>>,
+    inverse_kinematics: Vec<IkChain>,
+    ropes: Vec<RopeChain>,
+    layers: HashMap<ChainRef, CollisionLayers>,
+
+    order: Vec<Chain>,
+    dirty: bool,
+}
+
+impl Skeleton {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_node(&mut self, node: Node) -> NodeId {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.nodes.insert(id, RefCell::new(node));
+        id
+    }
+
+    pub fn add_fk(&mut self, nodes: Vec<NodeId>) -> usize {
+        self.forward_kinematics.push(without_repeats(nodes));
+        self.dirty = true;
+
+        self.forward_kinematics.len() - 1
+    }
+
+    /// The first node acts as the chain's anchor and is never moved by the solver.
+    pub fn add_ik(&mut self, nodes: Vec<NodeId>) -> usize {
+        let nodes = without_repeats(nodes);
+        let target = nodes
+            .last()
+            .and_then(|id| self.nodes.get(id))
+            .map(|node| node.borrow().pos)
+            .unwrap_or_default();
+
+        self.inverse_kinematics.push(IkChain {
+            nodes,
+            target,
+            limits: SolveLimits::default(),
+            report: SolveReport::default(),
+            pole: None,
+            bend: None,
+            solver: IkSolver::default(),
+            max_speed: None,
+        });
+        self.dirty = true;
+
+        self.inverse_kinematics.len() - 1
+    }
+
+    /// The first node is pinned to whatever drives it, the rest hang off it.
+    pub fn add_rope(&mut self, nodes: Vec<NodeId>) -> usize {
+        self.ropes.push(RopeChain::new(without_repeats(nodes)));
+        self.dirty = true;
+
+        self.ropes.len() - 1
+    }
+
+    #[inline]
+    pub fn get_node(&self, id: NodeId) -> Option<Ref<'_, Node>> {
+        self.nodes.get(&id).map(|node| node.borrow())
+    }
+
+    #[inline]
+    pub fn get_node_mut(&mut self, id: NodeId) -> Option<&mut Node> {
+        self.nodes.get_mut(&id).map(|node| node.get_mut())
+    }
+
+    #[inline]
+    pub fn get_fk(&self, index: usize) -> Option<&[NodeId]> {
+        self.forward_kinematics.get(index).map(|fk| fk.as_slice())
+    }
+
+    #[inline]
+    pub fn get_ik(&mut self, index: usize) -> Option<&mut IkChain> {
+        self.inverse_kinematics.get_mut(index)
+    }
+
+    #[inline]
+    pub fn get_rope(&mut self, index: usize) -> Option<&mut RopeChain> {
+        self.ropes.get_mut(index)
+    }
+
+    /// Collision layers of a chain, colliding with everything unless set.
+    #[inline]
+    pub fn layers(&self, chain: ChainRef) -> CollisionLayers {
+        self.layers.get(&chain).copied().unwrap_or_default()
+    }
+
+    #[inline]
+    pub fn set_layers(&mut self, chain: ChainRef, layers: CollisionLayers) {
+        self.layers.insert(chain, layers);
+    }
+
+    /// Every chain along with the nodes it references.
+    pub fn chains(&self) -> impl Iterator<Item = (ChainRef, &[NodeId])> {
+        let forward = self
+            .forward_kinematics
+            .iter()
+            .enumerate()
+            .map(|(index, nodes)| (ChainRef::Forward(index), nodes.as_slice()));
+        let inverse = self
+            .inverse_kinematics
+            .iter()
+            .enumerate()
+            .map(|(index, ik)| (ChainRef::Inverse(index), ik.nodes.as_slice()));
+        let ropes = self
+            .ropes
+            .iter()
+            .enumerate()
+            .map(|(index, rope)| (ChainRef::Rope(index), rope.nodes.as_slice()));
+
+        forward.chain(inverse).chain(ropes)
+    }
+
+    pub fn tick(&mut self) {
+        self.step(None);
+    }
+
+    /// Solve every chain in order. Forward kinematic chains integrate their node
+    /// springs and ropes are simulated when given a time step, otherwise both snap.
+    fn step(&mut self, dt: Option<f32>) {
+        if self.dirty {
+            self.order = self.resolve_order();
+            self.dirty = false;
+        }
+
+        let order = std::mem::take(&mut self.order);
+
+        order.iter().for_each(|chain| match chain {
+            Chain::Forward(index) => {
+                let nodes = self.collect_nodes(&self.forward_kinematics[*index]);
+                match dt {
+                    Some(dt) => spring::follow(&nodes, dt),
+                    None => forward(&nodes),
+                }
+            }
+            Chain::Rope(index) => {
+                let mut previous = std::mem::take(&mut self.ropes[*index].previous);
+
+                let rope = &self.ropes[*index];
+                let nodes = self.collect_nodes(&rope.nodes);
+                rope::simulate(&nodes, &mut previous, rope.settings, rope.force, dt);
+
+                let rope = &mut self.ropes[*index];
+                rope.previous = previous;
+                if dt.is_some() {
+                    rope.force = Vec2::ZERO;
+                }
+            }
+            Chain::Inverse(tree) if tree.len() == 1 => {
+                let ik = &self.inverse_kinematics[tree[0]];
+                let nodes = self.collect_nodes(&ik.nodes);
+
+                if let Some(anchor) = nodes.first().map(|node| node.borrow().pos) {
+                    let report = solve_chain(
+                        &ik.solver, &nodes, anchor, ik.target, ik.limits, ik.pole, ik.bend,
+                    );
+                    self.inverse_kinematics[tree[0]].report = report;
+                }
+            }
+            Chain::Inverse(tree) => {
+                self.fabrik_tree(tree)
+                    .into_iter()
+                    .for_each(|(index, report)| self.inverse_kinematics[index].report = report);
+            }
+        });
+
+        self.order = order;
+    }
+
+    /// Advance the skeleton by a time step. Same as [`Skeleton::tick`], except
+    /// inverse kinematic chains chase their targets no faster than their `max_speed`,
+    /// forward kinematic chains integrate their node springs and ropes swing under
+    /// gravity and forces.
+    pub fn update(&mut self, dt: f32) {
+        let targets = self
+            .inverse_kinematics
+            .iter()
+            .map(|chain| chain.target)
+            .collect::<Vec<_>>();
+
+        self.inverse_kinematics.iter_mut().for_each(|chain| {
+            let end = chain
+                .nodes
+                .last()
+                .and_then(|id| self.nodes.get(id))
+                .map(|node| node.borrow().pos);
+
+            chain.target = limit_target(end, chain.target, chain.max_speed, dt);
+        });
+
+        self.step(Some(dt));
+
+        self.inverse_kinematics
+            .iter_mut()
+            .zip(targets)
+            .for_each(|(chain, target)| chain.target = target);
+    }
+
+    /// Position and rotation of every node.
+    pub fn pose(&self) -> Pose {
+        Pose {
+            nodes: self
+                .nodes
+                .iter()
+                .map(|(id, node)| {
+                    let node = node.borrow();
+                    (*id, node.pos, node.rotation)
+                })
+                .collect(),
+        }
+    }
+
+    /// Move nodes to a pose. Nodes missing from either side are left alone.
+    pub fn set_pose(&mut self, pose: &Pose) {
+        pose.nodes.iter().for_each(|(id, pos, rotation)| {
+            if let Some(node) = self.get_node_mut(*id) {
+                node.pos = *pos;
+                node.rotation = *rotation;
+            }
+        });
+    }
+
+    fn collect_nodes(&self, ids: &[NodeId]) -> Vec<&RefCell<Node>> {
+        ids.iter().filter_map(|id| self.nodes.get(id)).collect()
+    }
+
+    /// Orders chains so that a chain is only resolved after the chain driving its root node.
+    fn resolve_order(&self) -> Vec<Chain> {
+        let chains = self.chains().collect::<Vec<_>>();
+
+        // Every node that isn't a chain root is driven by the first chain that claims it
+        let mut drivers = HashMap::new();
+        chains
+            .iter()
+            .enumerate()
+            .for_each(|(chain_index, (_, nodes))| {
+                nodes.iter().skip(1).for_each(|id| {
+                    drivers.entry(*id).or_insert(chain_index);
+                });
+            });
+
+        let parents = chains
+            .iter()
+            .enumerate()
+            .map(|(chain_index, (_, nodes))| {
+                nodes
+                    .first()
+                    .and_then(|root| drivers.get(root))
+                    .copied()
+                    .filter(|driver| *driver != chain_index)
+            })
+            .collect::<Vec<_>>();
+
+        let mut visited = vec![false; chains.len()];
+        let mut order = Vec::with_capacity(chains.len());
+
+        (0..chains.len()).for_each(|chain_index| {
+            let mut stack = Vec::new();
+            let mut current = Some(chain_index);
+
+            while let Some(index) = current {
+                if visited[index] {
+                    break;
+                }
+                if stack.contains(&index) {
+                    log::warn!("Skeleton chains form a cycle. Resolving in insertion order.");
+                    break;
+                }
+
+                stack.push(index);
+                current = parents[index];
+            }
+
+            stack.into_iter().rev().for_each(|index| {
+                visited[index] = true;
+                order.push(index);
+            });
+        });
+
+        // Inverse kinematic chains rooted on another inverse kinematic chain are merged
+        // into the tree of their top most inverse kinematic ancestor.
+        let is_inverse = |index: usize| matches!(chains[index].0, ChainRef::Inverse(_));
+        let tree_root = |mut index: usize| {
+            let mut steps = 0;
+            while let Some(parent) = parents[index].filter(|parent| is_inverse(*parent)) {
+                if steps > chains.len() {
+                    break;
+                }
+
+                index = parent;
+                steps += 1;
+            }
+            index
+        };
+
+        let mut trees = HashMap::<usize, Vec<usize>>::new();
+        order
+            .iter()
+            .filter(|index| is_inverse(**index))
+            .for_each(|index| {
+                let ChainRef::Inverse(ik_index) = chains[*index].0 else {
+                    unreachable!()
+                };
+                trees.entry(tree_root(*index)).or_default().push(ik_index);
+            });
+
+        order
+            .into_iter()
+            .filter_map(|index| match chains[index].0 {
+                ChainRef::Forward(fk_index) => Some(Chain::Forward(fk_index)),
+                ChainRef::Rope(rope_index) => Some(Chain::Rope(rope_index)),
+                ChainRef::Inverse(_) => trees.remove(&index).map(Chain::Inverse),
+            })
+            .collect()
+    }
+
+    /// Multi end effector FABRIK over a tree of inverse kinematic chains sharing sub-bases.
+    /// Each branch reaches towards its own target and sub-base positions are averaged
+    /// between branches on the backward pass. Targets of chains that other chains branch
+    /// off from the last node of are ignored. Returns a report for every chain with a target.
+    fn fabrik_tree(&self, tree: &[usize]) -> Vec<(usize, SolveReport)> {
+        // Flatten tree into nodes listed after their parents
+        let mut ids = Vec::<NodeId>::new();
+        let mut parents = Vec::<Option<usize>>::new();
+        let mut lookup = HashMap::new();
+
+        tree.iter().for_each(|ik_index| {
+            let ik = &self.inverse_kinematics[*ik_index];
+
+            ik.nodes.iter().fold(None, |parent, id| {
+                let index = *lookup.entry(*id).or_insert_with(|| {
+                    ids.push(*id);
+                    parents.push(parent);
+                    ids.len() - 1
+                });
+                Some(index)
+            });
+        });
+
+        let nodes = self.collect_nodes(&ids);
+        if nodes.len() != ids.len() || nodes.len() < 3 {
+            return Vec::new();
+        }
+
+        let mut children = vec![Vec::new(); nodes.len()];
+        parents.iter().enumerate().for_each(|(index, parent)| {
+            if let Some(parent) = parent {
+                children[*parent].push(index);
+            }
+        });
+
+        let targets = tree
+            .iter()
+            .filter_map(|ik_index| {
+                let ik = &self.inverse_kinematics[*ik_index];
+                let index = lookup[ik.nodes.last()?];
+                children[index]
+                    .is_empty()
+                    .then_some((index, (*ik_index, ik.target)))
+            })
+            .collect::<HashMap<_, _>>();
+
+        // Length of the stretched path from the tree root to each node
+        let mut lengths = vec![0.; nodes.len()];
+        (1..nodes.len()).for_each(|index| {
+            let parent = parents[index].unwrap();
+            lengths[index] = lengths[parent] + nodes[parent].borrow().radius;
+        });
+
+        let anchor = nodes[0].borrow().pos;
+        let initial_rot = nodes[0].borrow().rotation;
+        let limits = self.inverse_kinematics[tree[0]].limits;
+
+        let reports = |iterations: usize| {
+            targets
+                .iter()
+                .map(|(index, (ik_index, target))| {
+                    let error = nodes[*index].borrow().pos.distance(*target);
+                    let report = SolveReport {
+                        iterations,
+                        error,
+                        reached: error <= limits.tolerance,
+                        unreachable: anchor.distance(*target) > lengths[*index] + limits.tolerance,
+                        occluded: false,
+                    };
+                    (*ik_index, report)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        for iteration in 1..=limits.max_iterations {
+            (0..nodes.len()).rev().for_each(|index| {
+                if let Some((_, target)) = targets.get(&index) {
+                    nodes[index].borrow_mut().pos = *target;
+                    return;
+                }
+
+                let mut node = nodes[index].borrow_mut();
+
+                match children[index].as_slice() {
+                    [] => {}
+                    [child] => node.reach_towards(&nodes[*child].borrow()),
+                    branches => {
+                        let (pos, direction) = branches.iter().fold(
+                            (Vec2::ZERO, Vec2::ZERO),
+                            |(pos, direction), child| {
+                                let child = nodes[*child].borrow();
+                                let vector = Vec2::from_angle((child.pos - node.pos).to_angle());
+
+                                (pos + child.pos - vector * node.radius, direction + vector)
+                            },
+                        );
+
+                        node.pos = pos / branches.len() as f32;
+                        node.set_rotation(direction.to_angle());
+                    }
+                }
+            });
+
+            {
+                let mut node = nodes[0].borrow_mut();
+                node.pos = anchor;
+                node.rotation = initial_rot;
+            }
+
+            (1..nodes.len()).for_each(|index| {
+                let parent = nodes[parents[index].unwrap()].borrow();
+                nodes[index].borrow_mut().attach_rot(&parent);
+            });
+
+            let reports = reports(iteration);
+            if reports.iter().all(|(_, report)| report.reached) {
+                return reports;
+            }
+        }
+
+        reports(limits.max_iterations)
+    }
+
+    pub fn triangle_list(&self) -> Vec<Vec<[f32; 2]>> {
+        self.forward_kinematics
+            .iter()
+            .map(|skeleton| {
+                skeleton
+                    .iter()
+                    .filter_map(|id| self.nodes.get(id))
+                    .flat_map(|node| {
+                        let node = node.borrow();
+
+                        let node_right = node.get_point(node.get_rotation() - FRAC_PI_2).to_array();
+                        let node_left = node.get_point(node.get_rotation() + FRAC_PI_2).to_array();
+                        vec![node_right, node_left]
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Smooth body outlines around each forward kinematic chain and rope, matching
+    /// the edges of [`Skeleton::skin_meshes`].
+    pub fn body_outlines(&self, settings: SkinSettings) -> Vec<Vec<Vec2>> {
+        self.forward_kinematics
+            .iter()
+            .chain(self.ropes.iter().map(|rope| &rope.nodes))
+            .map(|chain| {
+                let nodes = chain
+                    .iter()
+                    .filter_map(|id| self.nodes.get(id))
+                    .map(|node| node.borrow())
+                    .collect::<Vec<_>>();
+
+                body_outline(
+                    &nodes.iter().map(|node| &**node).collect::<Vec<_>>(),
+                    settings,
+                )
+            })
+            .collect()
+    }
+
+    /// Smooth body skin meshes around each forward kinematic chain and rope.
+    pub fn skin_meshes(
+        &self,
+        settings: SkinSettings,
+        colors: &BodyColors,
+    ) -> Vec<(Vec<RawVertex>, Vec<u16>)> {
+        self.forward_kinematics
+            .iter()
+            .chain(self.ropes.iter().map(|rope| &rope.nodes))
+            .map(|skeleton| {
+                let nodes = skeleton
+                    .iter()
+                    .filter_map(|id| self.nodes.get(id))
+                    .map(|node| node.borrow())
+                    .collect::<Vec<_>>();
+                let nodes = nodes.iter().map(|node| &**node).collect::<Vec<_>>();
+
+                body_mesh(&nodes, settings, colors)
+            })
+            .collect()
+    }
+
+    /// Draw every chain's bones and node directions, along with inverse kinematic
+    /// targets and poles.
+    pub fn debug_draw(&self, draw: &mut DebugDraw) {
+        let borrow = |ids: &[NodeId]| {
+            ids.iter()
+                .filter_map(|id| self.nodes.get(id))
+                .map(|node| node.borrow())
+                .collect::<Vec<_>>()
+        };
+
+        self.forward_kinematics
+            .iter()
+            .chain(self.ropes.iter().map(|rope| &rope.nodes))
+            .for_each(|chain| {
+                let nodes = borrow(chain);
+                debug_draw_chain(draw, &nodes.iter().map(|node| &**node).collect::<Vec<_>>());
+            });
+
+        self.inverse_kinematics.iter().for_each(|chain| {
+            let nodes = borrow(&chain.nodes);
+            let nodes = nodes.iter().map(|node| &**node).collect::<Vec<_>>();
+
+            debug_draw_chain(draw, &nodes);
+            debug_draw_target(draw, &nodes, chain.target, chain.report.reached, chain.pole);
+        });
+    }
+
+    /// Draw the rotation range of every chain node relative to its parent.
+    pub fn debug_draw_limits(&self, draw: &mut DebugDraw) {
+        self.forward_kinematics
+            .iter()
+            .chain(self.inverse_kinematics.iter().map(|chain| &chain.nodes))
+            .chain(self.ropes.iter().map(|rope| &rope.nodes))
+            .for_each(|chain| {
+                let nodes = chain
+                    .iter()
+                    .filter_map(|id| self.nodes.get(id))
+                    .map(|node| node.borrow())
+                    .collect::<Vec<_>>();
+
+                debug_draw_limits(draw, &nodes.iter().map(|node| &**node).collect::<Vec<_>>());
+            });
+    }
+
+    pub fn circles(&self) -> Vec<RawInstance> {
+        let circles = self
+            .nodes
+            .values()
+            .map(|node| {
+                let node = node.borrow();
+
+                RawInstance::new(node.pos.to_array(), node.radius).hollow()
+            })
+            .collect::<Vec<_>>();
+
+        self.forward_kinematics
+            .iter()
+            .chain(self.inverse_kinematics.iter().map(|ik| &ik.nodes))
+            .chain(self.ropes.iter().map(|rope| &rope.nodes))
+            .fold(circles, |mut acc, chain| {
+                chain
+                    .iter()
+                    .filter_map(|id| self.nodes.get(id))
+                    .for_each(|node| {
+                        let node = node.borrow();
+                        acc.push(
+                            RawInstance::new(node.get_point(node.get_rotation()).to_array(), 5.)
+                                .with_color([1., 0., 0., 1.]),
+                        );
+                    });
+
+                acc
+            })
+    }
+
+    /// Export the smooth body outlines of forward kinematic chains and ropes along
+    /// with the node circles.
+    pub fn to_svg(&self) -> SvgDocument {
+        let mut document = SvgDocument::new();
+
+        self.body_outlines(SkinSettings::default())
+            .iter()
+            .for_each(|points| document.add_polygon(points, [1., 0., 0., 1.]));
+        document.add_circles(&self.circles());
+
+        document
+    }
+}
+
+//====================================================================
+
+pub fn spawn_creature(skeleton: &mut Skeleton) {
+    let nodes = [
+        Node::new(30.),
+        Node::locked(45., 0.),
+        Node::locked(50., 0.),
+        Node::new(40.),
+        Node::new(40.),
+        Node::unlocked(50.), // 5
+        Node::new(60.),
+        Node::new(63.),
+        Node::new(65.),
+        Node::new(63.),
+        Node::new(60.),
+        Node::new(40.),
+        Node::new(30.),
+        Node::new(20.),
+        Node::new(20.),
+        Node::new(20.),
+        Node::new(20.),
+        Node::new(20.),
+        Node::new(10.),
+        Node::new(10.),
+    ]
+    .into_iter()
+    .map(|node| skeleton.add_node(node))
+    .collect();
+
+    skeleton.add_fk(nodes);
+
+    let node = skeleton.add_node(Node::locked(40., 90.));
+    skeleton.add_fk(vec![5, node]);
+
+    let mut nodes = [
+        // Node::angles(20., 90., 90.),
+        // Node::locked(60., 0.),
+        Node::default(),
+        Node::default(),
+        Node::default(),
+        Node::default(),
+        Node::default(),
+    ]
+    .into_iter()
+    .map(|node| skeleton.add_node(node))
+    .collect::<Vec<_>>();
+
+    nodes.insert(0, node);
+
+    skeleton.add_ik(nodes);
+}
+
+//====================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two chains branching off the end of a shared inverse kinematic sub-base,
+    /// every node an unlocked link bent out of line. Returns both branches.
+    fn tree(skeleton: &mut Skeleton) -> (usize, usize) {
+        let mut add = |pos: Vec2| {
+            let mut node = Node::unlocked(50.);
+            node.pos = pos;
+            skeleton.add_node(node)
+        };
+
+        let root = add(vec2(0., 0.));
+        let middle = add(vec2(40., 30.));
+        let sub_base = add(vec2(80., 0.));
+        let upper = [add(vec2(120., 30.)), add(vec2(160., 0.))];
+        let lower = [add(vec2(120., -30.)), add(vec2(160., -60.))];
+
+        skeleton.add_ik(vec![root, middle, sub_base]);
+        let upper = skeleton.add_ik(vec![sub_base, upper[0], upper[1]]);
+        let lower = skeleton.add_ik(vec![sub_base, lower[0], lower[1]]);
+
+        (upper, lower)
+    }
+
+    fn solve(targets: [Vec2; 2]) -> [SolveReport; 2] {
+        let mut skeleton = Skeleton::new();
+        let branches = tree(&mut skeleton);
+
+        let limits = SolveLimits {
+            tolerance: 0.5,
+            max_iterations: 200,
+        };
+        [branches.0, branches.1]
+            .into_iter()
+            .zip(targets)
+            .for_each(|(branch, target)| {
+                let chain = skeleton.get_ik(branch).unwrap();
+                chain.target = target;
+                chain.limits = limits;
+            });
+        skeleton.get_ik(0).unwrap().limits = limits;
+
+        skeleton.tick();
+
+        [branches.0, branches.1].map(|branch| skeleton.get_ik(branch).unwrap().report)
+    }
+
+    #[test]
+    fn tree_reaches_every_target() {
+        let reports = solve([vec2(120., 80.), vec2(120., -80.)]);
+
+        reports.iter().for_each(|report| {
+            assert!(report.reached, "{report:?}");
+            assert!(!report.unreachable, "{report:?}");
+            assert!(report.error <= 0.5, "{report:?}");
+        });
+    }
+
+    #[test]
+    fn tree_branch_reaches_past_unreachable_sibling() {
+        let [far, near] = solve([vec2(1000., 0.), vec2(120., -80.)]);
+
+        assert!(far.unreachable, "{far:?}");
+        assert!(!far.reached, "{far:?}");
+
+        assert!(!near.unreachable, "{near:?}");
+        assert!(near.reached, "{near:?}");
+    }
+}
@@ -0,0 +1,77 @@
+//====================================================================
+
+use std::cell::RefCell;
+
+use glam::Vec2;
+
+use super::{forward, Node};
+
+//====================================================================
+
+/// Side of the chain middle joints should bend towards, relative to the
+/// direction running from the anchor towards the end effector.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bend {
+    Left,
+    Right,
+}
+
+impl Bend {
+    #[inline]
+    fn sign(&self) -> f32 {
+        match self {
+            Bend::Left => 1.,
+            Bend::Right => -1.,
+        }
+    }
+}
+
+//====================================================================
+
+/// Reflect every middle joint lying on the wrong side of the line between its
+/// neighbours. A pole target takes precedence over the bend direction. Reflecting
+/// keeps link lengths intact and rotation limits are reapplied afterwards, so limits
+/// win over the hint when the two disagree.
+pub(super) fn apply(nodes: &[&RefCell<Node>], pole: Option<Vec2>, bend: Option<Bend>) {
+    if nodes.len() < 3 || (pole.is_none() && bend.is_none()) {
+        return;
+    }
+
+    let mut changed = false;
+
+    (1..nodes.len() - 1).for_each(|index| {
+        let start = nodes[index - 1].borrow().pos;
+        let end = nodes[index + 1].borrow().pos;
+        let mut joint = nodes[index].borrow_mut();
+
+        let axis = end - start;
+        if axis == Vec2::ZERO {
+            return;
+        }
+
+        let side = axis.perp_dot(joint.pos - start);
+        let desired = match (pole, bend) {
+            (Some(pole), bend) => match axis.perp_dot(pole - start) {
+                0. => bend.map(|bend| bend.sign()).unwrap_or(0.),
+                desired => desired,
+            },
+            (None, Some(bend)) => bend.sign(),
+            (None, None) => 0.,
+        };
+
+        if side * desired >= 0. {
+            return;
+        }
+
+        let projected = start + axis * ((joint.pos - start).dot(axis) / axis.length_squared());
+        joint.pos = projected * 2. - joint.pos;
+        changed = true;
+    });
+
+    if changed {
+        forward(nodes);
+    }
+}
+
+//====================================================================
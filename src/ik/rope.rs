@@ -0,0 +1,204 @@
+//====================================================================
+
+use std::cell::RefCell;
+
+use glam::{vec2, Vec2};
+
+use super::{forward, wrap_angle, Node, NodeId};
+
+//====================================================================
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RopeSettings {
+    /// Acceleration applied to every free node, in units per second squared.
+    pub gravity: Vec2,
+    /// Exponential decay rate of velocity per second. Velocity shrinks by a factor
+    /// of `exp(-damping)` every second, so `0.5` keeps about 61% of it.
+    pub damping: f32,
+    /// Constraint relaxation passes per update. More passes make the rope stiffer.
+    pub iterations: usize,
+}
+
+impl Default for RopeSettings {
+    fn default() -> Self {
+        Self {
+            gravity: vec2(0., -980.),
+            damping: 0.5,
+            iterations: 8,
+        }
+    }
+}
+
+//====================================================================
+
+/// Chain simulated with Verlet integration. The first node is pinned and only
+/// moves when placed by hand, every other node swings under gravity and forces
+/// while keeping its link length and rotation limits.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rope {
+    pub nodes: Vec<RefCell<Node>>,
+    pub settings: RopeSettings,
+    /// Acceleration applied on top of gravity for the next update only.
+    pub force: Vec2,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    previous: Vec<Vec2>,
+}
+
+impl Rope {
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            settings: RopeSettings::default(),
+            force: Vec2::ZERO,
+            previous: Vec::new(),
+        }
+    }
+
+    pub fn with_settings(mut self, settings: RopeSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    pub fn with_nodes<T: IntoIterator<Item = Node>>(mut self, nodes: T) -> Self {
+        self.add_nodes(nodes);
+        self
+    }
+
+    pub fn add_nodes<T: IntoIterator<Item = Node>>(&mut self, nodes: T) {
+        nodes
+            .into_iter()
+            .for_each(|node| self.nodes.push(RefCell::new(node)));
+    }
+
+    /// Accelerate every free node for the next update, e.g. wind.
+    #[inline]
+    pub fn apply_force(&mut self, force: Vec2) {
+        self.force += force;
+    }
+
+    /// Satisfy constraints without moving the simulation forward.
+    pub fn tick(&mut self) {
+        let nodes = self.nodes.iter().collect::<Vec<_>>();
+        simulate(&nodes, &mut self.previous, self.settings, Vec2::ZERO, None);
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        let nodes = self.nodes.iter().collect::<Vec<_>>();
+        simulate(
+            &nodes,
+            &mut self.previous,
+            self.settings,
+            self.force,
+            Some(dt),
+        );
+        self.force = Vec2::ZERO;
+    }
+}
+
+impl Default for Rope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Rope referencing skeleton nodes. The root node is driven by whichever chain owns it.
+pub struct RopeChain {
+    pub nodes: Vec<NodeId>,
+    pub settings: RopeSettings,
+    /// Acceleration applied on top of gravity for the next update only.
+    pub force: Vec2,
+    pub(super) previous: Vec<Vec2>,
+}
+
+impl RopeChain {
+    pub(super) fn new(nodes: Vec<NodeId>) -> Self {
+        Self {
+            nodes,
+            settings: RopeSettings::default(),
+            force: Vec2::ZERO,
+            previous: Vec::new(),
+        }
+    }
+
+    /// Accelerate every free node for the next update, e.g. wind.
+    #[inline]
+    pub fn apply_force(&mut self, force: Vec2) {
+        self.force += force;
+    }
+}
+
+//====================================================================
+
+/// Integrate free nodes over `dt` when given, then relax link lengths and
+/// rotation limits. Finishes with a forward pass so every link ends up exact.
+pub(super) fn simulate(
+    nodes: &[&RefCell<Node>],
+    previous: &mut Vec<Vec2>,
+    settings: RopeSettings,
+    force: Vec2,
+    dt: Option<f32>,
+) {
+    if previous.len() != nodes.len() {
+        *previous = nodes.iter().map(|node| node.borrow().pos).collect();
+    }
+
+    if let Some(dt) = dt.filter(|dt| *dt > 0.) {
+        let retain = (-settings.damping.max(0.) * dt).exp();
+        let acceleration = (settings.gravity + force) * dt * dt;
+
+        nodes
+            .iter()
+            .zip(previous.iter_mut())
+            .skip(1)
+            .for_each(|(node, previous)| {
+                let mut node = node.borrow_mut();
+                let velocity = (node.pos - *previous) * retain;
+
+                *previous = node.pos;
+                node.pos += velocity + acceleration;
+            });
+    }
+
+    (0..settings.iterations).for_each(|_| {
+        nodes.windows(2).enumerate().for_each(|(index, pair)| {
+            let mut parent = pair[0].borrow_mut();
+            let mut child = pair[1].borrow_mut();
+
+            relax(&mut parent, &mut child, index == 0);
+        });
+    });
+
+    forward(nodes);
+}
+
+/// Pull a pair of nodes back to their link length, sharing the correction unless
+/// the parent is pinned, then swing the child back within its rotation limits.
+fn relax(parent: &mut Node, child: &mut Node, pinned: bool) {
+    let delta = parent.pos - child.pos;
+    let distance = delta.length();
+
+    if distance > f32::EPSILON {
+        let correction = delta * (1. - parent.radius / distance);
+
+        match pinned {
+            true => child.pos += correction,
+            false => {
+                parent.pos -= correction / 2.;
+                child.pos += correction / 2.;
+            }
+        }
+    }
+
+    let vector = parent.pos - child.pos;
+    let relative = wrap_angle(vector.to_angle() - parent.rotation);
+    let clamped = relative.clamp(child.min_rotation, child.max_rotation);
+
+    child.rotation = parent.rotation + clamped;
+    if clamped != relative {
+        child.pos = parent.pos - Vec2::from_angle(child.rotation) * vector.length();
+    }
+}
+
+//====================================================================
@@ -0,0 +1,274 @@
+//====================================================================
+
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+use glam::Vec2;
+
+use crate::renderer::polygon::{BodyColors, RawVertex};
+
+use super::Node;
+
+//====================================================================
+
+/// Resolution of a smooth body skin.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SkinSettings {
+    /// Points sampled along each side between two nodes. One gives straight edges.
+    pub subdivisions: u32,
+    /// Arc segments used for each of the head and tail caps.
+    pub cap_segments: u32,
+}
+
+impl Default for SkinSettings {
+    fn default() -> Self {
+        Self {
+            subdivisions: 4,
+            cap_segments: 8,
+        }
+    }
+}
+
+//====================================================================
+
+/// Sides and caps of a skin. Each cap arc runs between the ends of the two sides.
+struct SkinShape {
+    right: Vec<Vec2>,
+    left: Vec<Vec2>,
+    head: (Vec2, Vec<Vec2>),
+    tail: (Vec2, Vec<Vec2>),
+}
+
+impl SkinShape {
+    fn new(nodes: &[&Node], settings: SkinSettings) -> Option<Self> {
+        if nodes.len() < 2 {
+            return None;
+        }
+
+        let side = |offset: f32| {
+            let points = nodes
+                .iter()
+                .map(|node| node.get_point(node.get_rotation() + offset))
+                .collect::<Vec<_>>();
+
+            catmull_rom(&points, settings.subdivisions.max(1))
+        };
+
+        let first = nodes[0];
+        let last = nodes[nodes.len() - 1];
+
+        // Node rotations face the parent so the head cap wraps around the front of
+        // the first node and the tail cap around the back of the last.
+        Some(Self {
+            right: side(-FRAC_PI_2),
+            left: side(FRAC_PI_2),
+            head: (
+                first.pos,
+                arc(first, first.get_rotation() + FRAC_PI_2, -PI, settings),
+            ),
+            tail: (
+                last.pos,
+                arc(last, last.get_rotation() - FRAC_PI_2, -PI, settings),
+            ),
+        })
+    }
+}
+
+/// Points strictly between the start and end of an arc around a node.
+fn arc(node: &Node, start: f32, sweep: f32, settings: SkinSettings) -> Vec<Vec2> {
+    let segments = settings.cap_segments.max(1);
+
+    (1..segments)
+        .map(|index| node.get_point(start + sweep * index as f32 / segments as f32))
+        .collect()
+}
+
+/// Sample a uniform Catmull-Rom spline running through every point.
+fn catmull_rom(points: &[Vec2], subdivisions: u32) -> Vec<Vec2> {
+    let get = |index: isize| points[index.clamp(0, points.len() as isize - 1) as usize];
+
+    let mut samples = (0..points.len() as isize - 1)
+        .flat_map(|index| {
+            let (p0, p1, p2, p3) = (get(index - 1), get(index), get(index + 1), get(index + 2));
+
+            (0..subdivisions).map(move |step| {
+                let t = step as f32 / subdivisions as f32;
+                let (t2, t3) = (t * t, t * t * t);
+
+                0.5 * (2. * p1
+                    + (p2 - p0) * t
+                    + (2. * p0 - 5. * p1 + 4. * p2 - p3) * t2
+                    + (3. * p1 - p0 - 3. * p2 + p3) * t3)
+            })
+        })
+        .collect::<Vec<_>>();
+
+    samples.push(points[points.len() - 1]);
+    samples
+}
+
+//====================================================================
+
+/// Closed outline around a chain of nodes, starting at the head cap and running
+/// down the right side, around the tail and back up the left side.
+pub fn body_outline(nodes: &[&Node], settings: SkinSettings) -> Vec<Vec2> {
+    let Some(shape) = SkinShape::new(nodes, settings) else {
+        return match nodes.first() {
+            Some(node) => circle(node, settings),
+            None => Vec::new(),
+        };
+    };
+
+    let mut outline = Vec::with_capacity(
+        shape.right.len() + shape.left.len() + shape.head.1.len() + shape.tail.1.len(),
+    );
+
+    outline.extend(shape.head.1);
+    outline.extend(shape.right);
+    outline.extend(shape.tail.1);
+    outline.extend(shape.left.into_iter().rev());
+
+    outline
+}
+
+/// Triangulated skin around a chain of nodes that `PolygonPipeline` can draw,
+/// coloured by position along the chain.
+pub fn body_mesh(
+    nodes: &[&Node],
+    settings: SkinSettings,
+    colors: &BodyColors,
+) -> (Vec<RawVertex>, Vec<u16>) {
+    let count = nodes.len();
+    let mut mesh = Mesh::default();
+
+    let Some(shape) = SkinShape::new(nodes, settings) else {
+        let Some(node) = nodes.first() else {
+            return (Vec::new(), Vec::new());
+        };
+
+        let center = mesh.vertex(colors.vertex(node.pos.to_array(), 0., count, 0.));
+        let ring = circle(node, settings)
+            .into_iter()
+            .map(|point| {
+                let side =
+                    (point - node.pos).perp_dot(node.get_point(node.get_rotation()) - node.pos);
+                mesh.vertex(colors.vertex(
+                    point.to_array(),
+                    0.,
+                    count,
+                    side / node.radius.max(f32::EPSILON),
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        mesh.fan(center, ring.iter().chain(ring.first()).copied());
+        return mesh.finish();
+    };
+
+    let subdivisions = settings.subdivisions.max(1) as f32;
+    let mut side = |points: Vec<Vec2>, side: f32| {
+        points
+            .into_iter()
+            .enumerate()
+            .map(|(index, point)| {
+                mesh.vertex(colors.vertex(
+                    point.to_array(),
+                    index as f32 / subdivisions,
+                    count,
+                    side,
+                ))
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let right = side(shape.right, -1.);
+    let left = side(shape.left, 1.);
+
+    right
+        .windows(2)
+        .zip(left.windows(2))
+        .for_each(|(right, left)| {
+            mesh.triangle(right[0], right[1], left[0]);
+            mesh.triangle(left[0], right[1], left[1]);
+        });
+
+    // Caps sweep from one side to the other, fading the side colour through the centre
+    let mut cap = |(center, arc): (Vec2, Vec<Vec2>), node: f32, from: u16, to: u16, start: f32| {
+        let segments = arc.len() as f32 + 1.;
+
+        let center = mesh.vertex(colors.vertex(center.to_array(), node, count, 0.));
+        let arc = arc
+            .into_iter()
+            .enumerate()
+            .map(|(index, point)| {
+                let side = start * (PI * (index as f32 + 1.) / segments).cos();
+                mesh.vertex(colors.vertex(point.to_array(), node, count, side))
+            })
+            .collect::<Vec<_>>();
+
+        mesh.fan(
+            center,
+            std::iter::once(from).chain(arc).chain(std::iter::once(to)),
+        );
+    };
+
+    cap(shape.head, 0., left[0], right[0], 1.);
+    cap(
+        shape.tail,
+        (count - 1) as f32,
+        right[right.len() - 1],
+        left[left.len() - 1],
+        -1.,
+    );
+
+    mesh.finish()
+}
+
+fn circle(node: &Node, settings: SkinSettings) -> Vec<Vec2> {
+    let segments = settings.cap_segments.max(2) * 2;
+
+    (0..segments)
+        .map(|index| node.get_point(TAU * index as f32 / segments as f32))
+        .collect()
+}
+
+//====================================================================
+
+#[derive(Default)]
+struct Mesh {
+    vertices: Vec<RawVertex>,
+    indices: Vec<u16>,
+}
+
+impl Mesh {
+    fn vertex(&mut self, vertex: RawVertex) -> u16 {
+        self.vertices.push(vertex);
+        self.vertices.len() as u16 - 1
+    }
+
+    /// Add a triangle, wound counter clockwise so it survives back face culling.
+    fn triangle(&mut self, a: u16, b: u16, c: u16) {
+        let [pa, pb, pc] = [a, b, c].map(|index| Vec2::from(self.vertices[index as usize].pos));
+
+        match (pb - pa).perp_dot(pc - pa) < 0. {
+            true => self.indices.extend([a, c, b]),
+            false => self.indices.extend([a, b, c]),
+        }
+    }
+
+    fn fan(&mut self, center: u16, points: impl IntoIterator<Item = u16>) {
+        let points = points.into_iter().collect::<Vec<_>>();
+
+        points
+            .windows(2)
+            .for_each(|pair| self.triangle(center, pair[0], pair[1]));
+    }
+
+    #[inline]
+    fn finish(self) -> (Vec<RawVertex>, Vec<u16>) {
+        (self.vertices, self.indices)
+    }
+}
+
+//====================================================================
@@ -0,0 +1,245 @@
+//====================================================================
+
+use std::cell::RefCell;
+
+use glam::Vec2;
+
+use super::{ccd::Ccd, fabrik::Fabrik, jacobian::Jacobian, two_bone::TwoBone, Node};
+
+//====================================================================
+
+pub trait Solver {
+    /// Move the chain so its last node reaches towards `target` while its first node
+    /// stays on `anchor`.
+    fn solve(
+        &self,
+        nodes: &[&RefCell<Node>],
+        anchor: Vec2,
+        target: Vec2,
+        limits: SolveLimits,
+    ) -> SolveReport;
+
+    /// Same as [`Solver::solve`], bending towards `pole` when set. Solvers that
+    /// don't pick a bend themselves ignore it and leave bending to the chain.
+    fn solve_towards(
+        &self,
+        nodes: &[&RefCell<Node>],
+        anchor: Vec2,
+        target: Vec2,
+        pole: Option<Vec2>,
+        limits: SolveLimits,
+    ) -> SolveReport {
+        let _ = pole;
+        self.solve(nodes, anchor, target, limits)
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SolveLimits {
+    /// Distance from the target at which the end effector counts as having reached it.
+    pub tolerance: f32,
+    pub max_iterations: usize,
+}
+
+impl Default for SolveLimits {
+    fn default() -> Self {
+        Self {
+            tolerance: 0.01,
+            max_iterations: 10,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SolveReport {
+    pub iterations: usize,
+    /// Distance between the end effector and the target after solving.
+    pub error: f32,
+    pub reached: bool,
+    /// Target lies further from the anchor than the fully stretched chain can reach.
+    pub unreachable: bool,
+    /// Straight line from the anchor to the target crosses world geometry, or the
+    /// target is buried in it. Only checked when solving against a
+    /// [`World`](crate::world::World).
+    pub occluded: bool,
+}
+
+impl SolveReport {
+    pub fn new(
+        nodes: &[&RefCell<Node>],
+        anchor: Vec2,
+        target: Vec2,
+        iterations: usize,
+        limits: SolveLimits,
+    ) -> Self {
+        let error = nodes
+            .last()
+            .map(|node| node.borrow().pos.distance(target))
+            .unwrap_or(f32::INFINITY);
+
+        Self {
+            iterations,
+            error,
+            reached: error <= limits.tolerance,
+            unreachable: anchor.distance(target) > chain_length(nodes) + limits.tolerance,
+            occluded: false,
+        }
+    }
+}
+
+/// Length of the chain when fully stretched.
+pub fn chain_length(nodes: &[&RefCell<Node>]) -> f32 {
+    nodes
+        .iter()
+        .take(nodes.len().saturating_sub(1))
+        .map(|node| node.borrow().radius)
+        .sum()
+}
+
+//====================================================================
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum IkSolver {
+    /// FABRIK, switching to the analytic two bone solver for chains of three nodes.
+    #[default]
+    Fabrik,
+    Ccd,
+    Jacobian(Jacobian),
+    /// Bends towards the pole of the chain being solved.
+    TwoBone,
+}
+
+impl Solver for IkSolver {
+    fn solve(
+        &self,
+        nodes: &[&RefCell<Node>],
+        anchor: Vec2,
+        target: Vec2,
+        limits: SolveLimits,
+    ) -> SolveReport {
+        self.solve_towards(nodes, anchor, target, None, limits)
+    }
+
+    fn solve_towards(
+        &self,
+        nodes: &[&RefCell<Node>],
+        anchor: Vec2,
+        target: Vec2,
+        pole: Option<Vec2>,
+        limits: SolveLimits,
+    ) -> SolveReport {
+        match self {
+            IkSolver::Fabrik if nodes.len() == 3 => {
+                TwoBone.solve_towards(nodes, anchor, target, pole, limits)
+            }
+            IkSolver::Fabrik => Fabrik.solve(nodes, anchor, target, limits),
+            IkSolver::Ccd => Ccd.solve(nodes, anchor, target, limits),
+            IkSolver::Jacobian(jacobian) => jacobian.solve(nodes, anchor, target, limits),
+            IkSolver::TwoBone => TwoBone.solve_towards(nodes, anchor, target, pole, limits),
+        }
+    }
+}
+
+//====================================================================
+
+#[cfg(test)]
+mod tests {
+    use glam::vec2;
+
+    use super::*;
+
+    /// Unlocked chain of equal links bent into a zig zag, so solvers don't start
+    /// from a straight line.
+    fn chain(count: usize) -> Vec<RefCell<Node>> {
+        (0..count)
+            .map(|index| {
+                let mut node = Node::unlocked(50.);
+                node.pos = vec2(index as f32 * 40., (index % 2) as f32 * 30.);
+                RefCell::new(node)
+            })
+            .collect()
+    }
+
+    fn solve(solver: IkSolver, count: usize, target: Vec2, limits: SolveLimits) -> SolveReport {
+        let nodes = chain(count);
+        let nodes = nodes.iter().collect::<Vec<_>>();
+        solver.solve(&nodes, Vec2::ZERO, target, limits)
+    }
+
+    fn solvers() -> [IkSolver; 4] {
+        [
+            IkSolver::Fabrik,
+            IkSolver::Ccd,
+            IkSolver::Jacobian(Jacobian::default()),
+            IkSolver::TwoBone,
+        ]
+    }
+
+    #[test]
+    fn reachable_target_is_reached() {
+        let limits = SolveLimits {
+            tolerance: 0.5,
+            max_iterations: 200,
+        };
+
+        solvers().into_iter().for_each(|solver| {
+            let count = match solver {
+                IkSolver::TwoBone => 3,
+                _ => 4,
+            };
+            let report = solve(solver, count, vec2(60., 50.), limits);
+
+            assert!(report.reached, "{solver:?} {report:?}");
+            assert!(!report.unreachable, "{solver:?} {report:?}");
+            assert!(report.error <= limits.tolerance, "{solver:?} {report:?}");
+            assert!(
+                (1..=limits.max_iterations).contains(&report.iterations),
+                "{solver:?} {report:?}"
+            );
+        });
+    }
+
+    #[test]
+    fn unreachable_target_uses_every_iteration() {
+        let limits = SolveLimits::default();
+
+        solvers().into_iter().for_each(|solver| {
+            let report = solve(solver, 4, vec2(1000., 0.), limits);
+
+            assert!(report.unreachable, "{solver:?} {report:?}");
+            assert!(!report.reached, "{solver:?} {report:?}");
+
+            let expected = match solver {
+                // Only solves chains of three nodes
+                IkSolver::TwoBone => 0,
+                _ => limits.max_iterations,
+            };
+            assert_eq!(report.iterations, expected, "{solver:?}");
+        });
+    }
+
+    #[test]
+    fn two_bone_solves_in_one_iteration() {
+        let report = solve(
+            IkSolver::TwoBone,
+            3,
+            vec2(1000., 0.),
+            SolveLimits::default(),
+        );
+
+        assert_eq!(report.iterations, 1);
+        assert!(report.unreachable);
+        assert!((report.error - 900.).abs() < 0.01, "{report:?}");
+    }
+
+    #[test]
+    fn chain_length_skips_last_node() {
+        let nodes = chain(4);
+        let nodes = nodes.iter().collect::<Vec<_>>();
+
+        assert_eq!(chain_length(&nodes), 150.);
+        assert_eq!(chain_length(&nodes[..1]), 0.);
+    }
+}
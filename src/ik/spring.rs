@@ -0,0 +1,102 @@
+//====================================================================
+
+use std::cell::RefCell;
+
+use glam::Vec2;
+
+use super::{wrap_angle, Node};
+
+//====================================================================
+
+/// Damped spring pulling a value towards a target. Integrated with semi-implicit
+/// Euler, so keep `stiffness * dt * dt` well below 4 to stay stable.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Spring {
+    pub stiffness: f32,
+    pub damping: f32,
+}
+
+impl Spring {
+    pub fn new(stiffness: f32, damping: f32) -> Self {
+        Self { stiffness, damping }
+    }
+
+    /// Spring that settles as fast as possible without overshooting.
+    pub fn critical(stiffness: f32) -> Self {
+        Self {
+            stiffness,
+            damping: 2. * stiffness.max(0.).sqrt(),
+        }
+    }
+
+    /// Velocity after accelerating towards the target over `dt`.
+    #[inline]
+    fn accelerate<T>(&self, offset: T, velocity: T, dt: f32) -> T
+    where
+        T: std::ops::Mul<f32, Output = T>
+            + std::ops::Sub<Output = T>
+            + std::ops::Add<Output = T>
+            + Copy,
+    {
+        velocity + (offset * self.stiffness - velocity * self.damping) * dt
+    }
+}
+
+//====================================================================
+
+impl Node {
+    /// Like [`Node::attach_rot`], but eases towards the attached rotation and
+    /// position using the node's springs. Without springs this snaps the same way.
+    pub fn follow(&mut self, parent: &Node, dt: f32) {
+        let vector = parent.pos - self.pos;
+        let target = parent.rotation
+            + wrap_angle(vector.to_angle() - parent.rotation)
+                .clamp(self.min_rotation, self.max_rotation);
+
+        match self.rotation_spring {
+            Some(spring) => {
+                let offset = wrap_angle(target - self.rotation);
+                self.angular_velocity = spring.accelerate(offset, self.angular_velocity, dt);
+
+                let relative =
+                    wrap_angle(self.rotation + self.angular_velocity * dt - parent.rotation);
+                let clamped = relative.clamp(self.min_rotation, self.max_rotation);
+
+                // Stop at the limits instead of pushing against them
+                if clamped != relative {
+                    self.angular_velocity = 0.;
+                }
+                self.rotation = parent.rotation + clamped;
+            }
+            None => {
+                self.rotation = target;
+                self.angular_velocity = 0.;
+            }
+        }
+
+        let attached = parent.pos - Vec2::from_angle(self.rotation) * parent.radius;
+
+        match self.position_spring {
+            Some(spring) => {
+                self.velocity = spring.accelerate(attached - self.pos, self.velocity, dt);
+                self.pos += self.velocity * dt;
+            }
+            None => {
+                self.pos = attached;
+                self.velocity = Vec2::ZERO;
+            }
+        }
+    }
+}
+
+pub(super) fn follow(nodes: &[&RefCell<Node>], dt: f32) {
+    nodes.windows(2).for_each(|pair| {
+        let first = pair[0].borrow();
+        let mut second = pair[1].borrow_mut();
+
+        second.follow(&first, dt);
+    });
+}
+
+//====================================================================
@@ -0,0 +1,130 @@
+//====================================================================
+
+use std::cell::RefCell;
+
+use glam::{Mat2, Vec2};
+
+use super::{
+    forward,
+    solver::{SolveLimits, SolveReport, Solver},
+    wrap_angle, Node,
+};
+
+//====================================================================
+
+/// Closed form law of cosines solver for chains of exactly three nodes. Bends towards
+/// the pole when set, otherwise keeps the side the middle joint is currently on. The
+/// opposite bend is used when the preferred one breaks rotation limits, and if both
+/// do the preferred bend is clamped to the limits.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TwoBone;
+
+impl Solver for TwoBone {
+    fn solve(
+        &self,
+        nodes: &[&RefCell<Node>],
+        anchor: Vec2,
+        target: Vec2,
+        limits: SolveLimits,
+    ) -> SolveReport {
+        self.solve_towards(nodes, anchor, target, None, limits)
+    }
+
+    fn solve_towards(
+        &self,
+        nodes: &[&RefCell<Node>],
+        anchor: Vec2,
+        target: Vec2,
+        pole: Option<Vec2>,
+        limits: SolveLimits,
+    ) -> SolveReport {
+        if nodes.len() != 3 {
+            return SolveReport::new(nodes, anchor, target, 0, limits);
+        }
+
+        nodes[0].borrow_mut().pos = anchor;
+
+        let upper = nodes[0].borrow().radius;
+        let lower = nodes[1].borrow().radius;
+
+        let to_target = target - anchor;
+        let direction = to_target
+            .try_normalize()
+            .unwrap_or_else(|| -Vec2::from_angle(nodes[0].borrow().rotation));
+        let distance = to_target
+            .length()
+            .clamp((upper - lower).abs(), upper + lower);
+
+        let cos_angle = (upper * upper + distance * distance - lower * lower)
+            / (2. * upper * distance).max(f32::EPSILON);
+        let angle = cos_angle.clamp(-1., 1.).acos();
+
+        let reference = pole.unwrap_or(nodes[1].borrow().pos) - anchor;
+        let side = match direction.perp_dot(reference) {
+            side if side < 0. => -1.,
+            _ => 1.,
+        };
+
+        let preferred = bend(nodes, anchor, target, direction, angle * side);
+        if within_limits(nodes, &preferred) {
+            place(nodes, &preferred);
+        } else {
+            let opposite = bend(nodes, anchor, target, direction, -angle * side);
+
+            if within_limits(nodes, &opposite) {
+                place(nodes, &opposite);
+            } else {
+                place(nodes, &preferred);
+                forward(nodes);
+            }
+        }
+
+        SolveReport::new(nodes, anchor, target, 1, limits)
+    }
+}
+
+/// Positions and rotations of the middle and end node for a given bend angle.
+fn bend(
+    nodes: &[&RefCell<Node>],
+    anchor: Vec2,
+    target: Vec2,
+    direction: Vec2,
+    angle: f32,
+) -> [(Vec2, f32); 2] {
+    let upper = nodes[0].borrow().radius;
+    let lower = nodes[1].borrow().radius;
+
+    let middle = anchor + Mat2::from_angle(angle) * direction * upper;
+    let end = middle + (target - middle).try_normalize().unwrap_or(direction) * lower;
+
+    [
+        (middle, (anchor - middle).to_angle()),
+        (end, (middle - end).to_angle()),
+    ]
+}
+
+fn within_limits(nodes: &[&RefCell<Node>], solution: &[(Vec2, f32); 2]) -> bool {
+    let root_rotation = nodes[0].borrow().rotation;
+
+    let middle = nodes[1].borrow();
+    let end = nodes[2].borrow();
+
+    let middle_diff = wrap_angle(solution[0].1 - root_rotation);
+    let end_diff = wrap_angle(solution[1].1 - solution[0].1);
+
+    (middle.min_rotation..=middle.max_rotation).contains(&middle_diff)
+        && (end.min_rotation..=end.max_rotation).contains(&end_diff)
+}
+
+fn place(nodes: &[&RefCell<Node>], solution: &[(Vec2, f32); 2]) {
+    nodes[1..]
+        .iter()
+        .zip(solution.iter())
+        .for_each(|(node, (pos, rotation))| {
+            let mut node = node.borrow_mut();
+            node.pos = *pos;
+            node.rotation = *rotation;
+        });
+}
+
+//====================================================================
@@ -1,2 +1,6 @@
+pub mod creature;
 pub mod ik;
+pub mod locomotion;
 pub mod renderer;
+pub mod timestep;
+pub mod world;
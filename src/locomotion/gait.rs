@@ -0,0 +1,98 @@
+//====================================================================
+
+/// Named stepping patterns. Phases are assigned from each leg's position on the
+/// body, counting pairs from the front and using the side of its rest offset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Gait {
+    /// Tetrapod lateral sequence walk, one leg lifted at a time.
+    Walk,
+    /// Diagonal pairs lifted together.
+    Trot,
+    /// Front legs followed by hind legs, each pair slightly staggered.
+    Gallop,
+    /// Slow sprawling walk, one leg lifted at a time with long stances.
+    Crawl,
+    /// Hexapod tripod, alternating legs lifted together.
+    Tripod,
+    /// Metachronal wave running from the back to the front, as in centipedes.
+    Wave,
+}
+
+impl Gait {
+    /// Offset into the gait cycle, between 0 and 1, at which a leg lifts.
+    pub fn phase(&self, pair: usize, pairs: usize, left: bool) -> f32 {
+        let pairs = pairs.max(1);
+        let side = if left { 0. } else { 0.5 };
+
+        match self {
+            Gait::Walk | Gait::Crawl => {
+                let left_phase = (pairs - 1 - pair.min(pairs - 1)) as f32 / pairs as f32 * 0.5;
+                (left_phase + side).fract()
+            }
+            Gait::Trot | Gait::Tripod => ((pair + left as usize) % 2) as f32 * 0.5,
+            Gait::Gallop => {
+                let hind = if pair * 2 >= pairs { 0.5 } else { 0. };
+                let stagger = if left { 0. } else { 0.1 };
+                hind + stagger
+            }
+            Gait::Wave => {
+                let left_phase = 1. - (pair + 1) as f32 / pairs as f32;
+                (left_phase + side).fract()
+            }
+        }
+    }
+
+    /// Fraction of the cycle each leg spends lifted.
+    pub fn duty(&self, pairs: usize) -> f32 {
+        let legs = (pairs.max(1) * 2) as f32;
+
+        match self {
+            Gait::Walk => 1. / legs,
+            Gait::Crawl => 0.8 / legs,
+            Gait::Trot | Gait::Tripod => 0.5,
+            Gait::Gallop => 0.4,
+            Gait::Wave => 2. / legs,
+        }
+    }
+
+    /// Time in seconds of a full cycle.
+    pub fn cycle_duration(&self) -> f32 {
+        match self {
+            Gait::Walk => 0.8,
+            Gait::Crawl => 1.2,
+            Gait::Trot | Gait::Tripod => 0.5,
+            Gait::Gallop => 0.4,
+            Gait::Wave => 1.,
+        }
+    }
+}
+
+//====================================================================
+
+/// Gaits picked by body speed, each used from its minimum speed upwards.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GaitSpeeds {
+    pub gaits: Vec<(f32, Gait)>,
+    /// Time in seconds taken to blend phases and timings into a new gait.
+    pub blend: f32,
+}
+
+impl GaitSpeeds {
+    pub fn new<T: IntoIterator<Item = (f32, Gait)>>(gaits: T) -> Self {
+        let mut gaits = gaits.into_iter().collect::<Vec<_>>();
+        gaits.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        Self { gaits, blend: 0.3 }
+    }
+
+    pub fn select(&self, speed: f32) -> Option<Gait> {
+        self.gaits
+            .iter()
+            .rev()
+            .find(|(min_speed, _)| speed >= *min_speed)
+            .or(self.gaits.first())
+            .map(|(_, gait)| *gait)
+    }
+}
+
+//====================================================================
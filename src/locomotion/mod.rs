@@ -0,0 +1,498 @@
+//====================================================================
+
+use std::f32::consts::PI;
+
+use glam::{Mat2, Vec2};
+
+use crate::{
+    ik::{ForwardKinematic, InverseKinematic, SolveReport},
+    renderer::circles::RawInstance,
+    world::World,
+};
+
+mod gait;
+
+pub use gait::{Gait, GaitSpeeds};
+
+//====================================================================
+
+const FOOT_RADIUS: f32 = 6.;
+/// Marker radius gained per unit of foot height.
+const FOOT_HEIGHT_SCALE: f32 = 0.5;
+const FOOT_COLOR: [f32; 4] = [0.9, 0.6, 0.1, 1.];
+
+//====================================================================
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StepSettings {
+    /// Distance a planted foot may drift from its rest position before stepping.
+    pub distance: f32,
+    /// Drift at which a foot steps regardless of its gait phase.
+    pub max_distance: f32,
+    /// Peak height of a foot halfway through a step.
+    pub height: f32,
+    /// Time in seconds a single step takes. Replaced by the timing of the gait
+    /// once one is set.
+    pub duration: f32,
+    /// Time in seconds of a full gait cycle in which every leg gets a chance to step.
+    /// Replaced by the timing of the gait once one is set.
+    pub cycle_duration: f32,
+    /// How far past the rest position a step lands, as a fraction of the drift.
+    pub overshoot: f32,
+}
+
+impl Default for StepSettings {
+    fn default() -> Self {
+        Self {
+            distance: 60.,
+            max_distance: 120.,
+            height: 20.,
+            duration: 0.15,
+            cycle_duration: 0.6,
+            overshoot: 0.5,
+        }
+    }
+}
+
+//====================================================================
+
+/// Step timing taken from the active gait, kept apart from [`StepSettings`] so
+/// the configured timing survives switching gaits on and off.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct GaitTiming {
+    duration: f32,
+    cycle_duration: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Step {
+    from: Vec2,
+    to: Vec2,
+    elapsed: f32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Foot {
+    /// Position of the foot on the ground plane.
+    pub pos: Vec2,
+    /// Height of the foot above the ground. Kept separate from `pos` since the
+    /// world is seen from above.
+    pub height: f32,
+    step: Option<Step>,
+}
+
+impl Foot {
+    #[inline]
+    pub fn is_stepping(&self) -> bool {
+        self.step.is_some()
+    }
+}
+
+pub struct Leg {
+    pub ik: InverseKinematic,
+    /// Index of the body node the leg is attached to.
+    pub body_node: usize,
+    /// Rest position of the foot relative to the body node, where `x` points
+    /// along the body towards the head and `y` to the body's left.
+    pub rest_offset: Vec2,
+    /// Offset into the gait cycle, between 0 and 1, at which the leg may step.
+    pub phase: f32,
+    /// Legs in different groups never start stepping while the other is lifted.
+    pub group: Option<usize>,
+    pub foot: Foot,
+    pub report: SolveReport,
+}
+
+impl Leg {
+    pub fn new(ik: InverseKinematic, body_node: usize, rest_offset: Vec2) -> Self {
+        Self {
+            ik,
+            body_node,
+            rest_offset,
+            phase: 0.,
+            group: None,
+            foot: Foot::default(),
+            report: SolveReport::default(),
+        }
+    }
+
+    pub fn with_phase(mut self, phase: f32) -> Self {
+        self.phase = phase;
+        self
+    }
+
+    /// Ideal position of the foot given the current pose of the body.
+    pub fn rest_position(&self, body: &ForwardKinematic) -> Option<Vec2> {
+        let node = body.nodes.get(self.body_node)?;
+        Some(node.pos + Mat2::from_angle(node.get_rotation()) * self.rest_offset)
+    }
+}
+
+//====================================================================
+
+/// Moves the feet of a set of legs as their body moves, planting them on the
+/// ground and stepping them towards their rest positions when they drift too far.
+pub struct Locomotion {
+    pub body: ForwardKinematic,
+    pub legs: Vec<Leg>,
+    pub settings: StepSettings,
+    /// Gaits switched between automatically based on body speed.
+    pub gait_speeds: Option<GaitSpeeds>,
+
+    gait: Option<Gait>,
+    gait_timing: Option<GaitTiming>,
+    cycle: f32,
+    speed: f32,
+    last_root: Option<Vec2>,
+}
+
+impl Locomotion {
+    pub fn new(body: ForwardKinematic) -> Self {
+        Self {
+            body,
+            legs: Vec::new(),
+            settings: StepSettings::default(),
+            gait_speeds: None,
+            gait: None,
+            gait_timing: None,
+            cycle: 0.,
+            speed: 0.,
+            last_root: None,
+        }
+    }
+
+    pub fn with_gait(mut self, gait: Gait) -> Self {
+        self.set_gait(gait);
+        self
+    }
+
+    pub fn with_gait_speeds(mut self, gait_speeds: GaitSpeeds) -> Self {
+        self.gait_speeds = Some(gait_speeds);
+        self
+    }
+
+    pub fn with_settings(mut self, settings: StepSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    pub fn with_leg(mut self, leg: Leg) -> Self {
+        self.add_leg(leg);
+        self
+    }
+
+    /// Add a leg with its foot planted at its current rest position.
+    pub fn add_leg(&mut self, mut leg: Leg) {
+        if let Some(rest) = leg.rest_position(&self.body) {
+            leg.foot.pos = rest;
+        }
+        self.legs.push(leg);
+    }
+
+    /// Position within the gait cycle, between 0 and 1.
+    #[inline]
+    pub fn cycle(&self) -> f32 {
+        self.cycle
+    }
+
+    /// Smoothed speed of the body's first node.
+    #[inline]
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    #[inline]
+    pub fn gait(&self) -> Option<Gait> {
+        self.gait
+    }
+
+    /// Time in seconds a single step takes, from the gait when one is set.
+    #[inline]
+    pub fn step_duration(&self) -> f32 {
+        self.gait_timing
+            .map_or(self.settings.duration, |timing| timing.duration)
+    }
+
+    /// Time in seconds of a full gait cycle, from the gait when one is set.
+    #[inline]
+    pub fn cycle_duration(&self) -> f32 {
+        self.gait_timing
+            .map_or(self.settings.cycle_duration, |timing| timing.cycle_duration)
+    }
+
+    /// Drop the gait, returning to the timing in `settings`.
+    pub fn clear_gait(&mut self) {
+        self.gait = None;
+        self.gait_timing = None;
+    }
+
+    /// Switch to a gait immediately, assigning phases and groups to legs. When
+    /// `gait_speeds` is set the gait is instead picked and blended into every tick.
+    pub fn set_gait(&mut self, gait: Gait) {
+        self.gait = Some(gait);
+        self.blend_gait(gait, 1.);
+    }
+
+    /// Pair index counted from the front and side of every leg.
+    fn leg_layout(&self) -> (Vec<(usize, bool)>, usize) {
+        let mut layout = vec![(0, false); self.legs.len()];
+        let mut pairs = 0;
+
+        [true, false].into_iter().for_each(|left| {
+            let mut side = self
+                .legs
+                .iter()
+                .enumerate()
+                .filter(|(_, leg)| (leg.rest_offset.y >= 0.) == left)
+                .collect::<Vec<_>>();
+
+            side.sort_by(|(_, a), (_, b)| {
+                a.body_node
+                    .cmp(&b.body_node)
+                    .then(b.rest_offset.x.total_cmp(&a.rest_offset.x))
+            });
+
+            side.iter().enumerate().for_each(|(pair, (index, _))| {
+                layout[*index] = (pair, left);
+            });
+            pairs = pairs.max(side.len());
+        });
+
+        (layout, pairs)
+    }
+
+    /// Move leg phases and step timings a fraction of the way towards a gait.
+    fn blend_gait(&mut self, gait: Gait, amount: f32) {
+        let (layout, pairs) = self.leg_layout();
+        let mut groups = Vec::<f32>::new();
+
+        self.legs
+            .iter_mut()
+            .zip(layout)
+            .for_each(|(leg, (pair, left))| {
+                let target = gait.phase(pair, pairs, left);
+                let difference = (target - leg.phase + 0.5).rem_euclid(1.) - 0.5;
+                leg.phase = (leg.phase + difference * amount).rem_euclid(1.);
+
+                let group = groups
+                    .iter()
+                    .position(|phase| (phase - target).abs() < f32::EPSILON)
+                    .unwrap_or_else(|| {
+                        groups.push(target);
+                        groups.len() - 1
+                    });
+                leg.group = Some(group);
+            });
+
+        let cycle_duration = gait.cycle_duration();
+        let duration = gait.duty(pairs) * cycle_duration;
+
+        let current = GaitTiming {
+            duration: self.step_duration(),
+            cycle_duration: self.cycle_duration(),
+        };
+
+        self.gait_timing = Some(GaitTiming {
+            duration: current.duration + (duration - current.duration) * amount,
+            cycle_duration: current.cycle_duration
+                + (cycle_duration - current.cycle_duration) * amount,
+        });
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        self.step(dt, None);
+    }
+
+    /// Same as [`Locomotion::tick`], except feet never land inside or against world
+    /// geometry and leg reports flag targets blocked from the leg's anchor. The
+    /// world is read top-down like the body, so shapes act as obstacles.
+    pub fn tick_in(&mut self, dt: f32, world: &World) {
+        self.step(dt, Some(world));
+    }
+
+    fn step(&mut self, dt: f32, world: Option<&World>) {
+        match dt > 0. {
+            true => self.body.update(dt),
+            false => self.body.tick(),
+        }
+
+        if let Some(root) = self.body.nodes.first().map(|node| node.pos) {
+            if let Some(last_root) = self.last_root.filter(|_| dt > 0.) {
+                let speed = root.distance(last_root) / dt;
+                self.speed += (speed - self.speed) * (dt * 5.).min(1.);
+            }
+            self.last_root = Some(root);
+        }
+
+        if let Some(gait_speeds) = &self.gait_speeds {
+            let amount = match gait_speeds.blend > 0. {
+                true => (dt / gait_speeds.blend).min(1.),
+                false => 1.,
+            };
+
+            if let Some(gait) = gait_speeds.select(self.speed) {
+                self.gait = Some(gait);
+                self.blend_gait(gait, amount);
+            }
+        }
+
+        let duration = self.step_duration();
+        let cycle_duration = self.cycle_duration();
+
+        self.cycle = (self.cycle + dt / cycle_duration).fract();
+
+        let cycle = self.cycle;
+        let window = duration / cycle_duration;
+
+        // Groups with a foot currently in the air
+        let lifted = self
+            .legs
+            .iter()
+            .filter(|leg| leg.foot.is_stepping())
+            .filter_map(|leg| leg.group)
+            .collect::<Vec<_>>();
+
+        let settings = self.settings;
+        let body = &self.body;
+
+        self.legs.iter_mut().for_each(|leg| {
+            let Some(rest) = leg.rest_position(body) else {
+                return;
+            };
+
+            match &mut leg.foot.step {
+                Some(step) => {
+                    step.elapsed += dt;
+                    let progress = (step.elapsed / duration).min(1.);
+                    let eased = progress * progress * (3. - 2. * progress);
+
+                    leg.foot.pos = step.from.lerp(step.to, eased);
+                    leg.foot.height = settings.height * (progress * PI).sin();
+
+                    if progress >= 1. {
+                        leg.foot.step = None;
+                        leg.foot.height = 0.;
+                    }
+                }
+
+                None => {
+                    let drift = rest - leg.foot.pos;
+                    let in_phase = (cycle - leg.phase).rem_euclid(1.) < window;
+                    let blocked = leg
+                        .group
+                        .is_some_and(|group| lifted.iter().any(|lifted| *lifted != group));
+
+                    if (drift.length() > settings.distance && in_phase && !blocked)
+                        || drift.length() > settings.max_distance
+                    {
+                        let to = rest + drift * settings.overshoot;
+                        let to = match world {
+                            Some(world) => world.push_out(to, FOOT_RADIUS).0,
+                            None => to,
+                        };
+
+                        leg.foot.step = Some(Step {
+                            from: leg.foot.pos,
+                            to,
+                            elapsed: 0.,
+                        });
+                    }
+                }
+            }
+
+            let anchor = body.nodes[leg.body_node].pos;
+            leg.ik.anchor = anchor;
+            leg.ik.target = leg.foot.pos;
+
+            if let Some(root) = leg.ik.nodes.first() {
+                let mut root = root.borrow_mut();
+                root.pos = anchor;
+                root.set_rotation((anchor - rest).to_angle());
+            }
+
+            leg.report = match world {
+                Some(world) => leg.ik.solve_in(world),
+                None => leg.ik.solve(),
+            };
+        });
+    }
+
+    /// Body and leg nodes, plus a marker on every foot that grows with the foot's
+    /// height so lifted feet read as closer to the viewer.
+    pub fn circles(&self) -> Vec<RawInstance> {
+        self.body
+            .nodes
+            .iter()
+            .map(|node| RawInstance::new(node.pos.to_array(), node.radius).hollow())
+            .chain(self.legs.iter().flat_map(|leg| leg.ik.circles()))
+            .chain(self.legs.iter().map(|leg| {
+                RawInstance::new(
+                    leg.foot.pos.to_array(),
+                    FOOT_RADIUS + leg.foot.height * FOOT_HEIGHT_SCALE,
+                )
+                .with_color(FOOT_COLOR)
+            }))
+            .collect()
+    }
+}
+
+//====================================================================
+
+#[cfg(test)]
+mod tests {
+    use glam::vec2;
+
+    use crate::ik::Node;
+
+    use super::*;
+
+    fn walker() -> Locomotion {
+        let body = ForwardKinematic {
+            nodes: vec![Node::new(50.), Node::new(50.)],
+        };
+        let leg = InverseKinematic::new(Vec2::ZERO, Vec2::ZERO).with_nodes([
+            Node::unlocked(40.),
+            Node::unlocked(40.),
+            Node::unlocked(40.),
+        ]);
+
+        Locomotion::new(body).with_leg(Leg::new(leg, 0, vec2(0., 60.)))
+    }
+
+    #[test]
+    fn drifted_foot_steps_past_rest() {
+        let mut locomotion = walker();
+        let dt = 0.01;
+
+        locomotion.tick(dt);
+        assert!(!locomotion.legs[0].foot.is_stepping());
+
+        let from = locomotion.legs[0].foot.pos;
+        locomotion.body.nodes[0].pos.x += locomotion.settings.distance + 10.;
+        locomotion.tick(dt);
+
+        let leg = &locomotion.legs[0];
+        assert!(leg.foot.is_stepping());
+
+        let rest = leg.rest_position(&locomotion.body).unwrap();
+        let expected = rest + (rest - from) * locomotion.settings.overshoot;
+
+        // The step starts without advancing, so it is lifted until just past its duration
+        let ticks = (locomotion.step_duration() / dt).round() as usize;
+        (1..ticks).for_each(|_| locomotion.tick(dt));
+        assert!(locomotion.legs[0].foot.is_stepping());
+        assert!(locomotion.legs[0].foot.height > 0.);
+
+        locomotion.tick(dt);
+        locomotion.tick(dt);
+
+        let foot = locomotion.legs[0].foot;
+        assert!(!foot.is_stepping());
+        assert_eq!(foot.height, 0.);
+        assert!(
+            foot.pos.distance(expected) < 0.01,
+            "{:?} {expected:?}",
+            foot.pos
+        );
+    }
+}
@@ -3,12 +3,21 @@
 use std::sync::Arc;
 
 use glam::{vec2, Vec2};
+#[cfg(feature = "serde")]
+use ik_creatures::creature::CreatureWatcher;
 use ik_creatures::{
-    ik::{InverseKinematic, Node},
+    creature::{ChainDef, Creature, CreatureDef, NodeDef},
+    ik::{CollisionSettings, Pose, SkinSettings},
     renderer::{
-        circles::CirclePipeline, polygon::PolygonPipeline, text::TextPipeline,
-        uniques::OrthographicCamera, Renderer,
+        circles::CirclePipeline,
+        debug::{DebugDraw, DebugPipeline},
+        polygon::PolygonPipeline,
+        text::{TextLabel, TextPipeline},
+        uniques::OrthographicCamera,
+        Renderer, RendererSettings,
     },
+    timestep::FixedTimestep,
+    world::{Heightfield, Shape, World},
 };
 use pollster::FutureExt;
 use winit::{
@@ -18,12 +27,16 @@ use winit::{
 
 //====================================================================
 
+#[cfg(feature = "serde")]
+const DEFAULT_CREATURE: &str = "creatures/lizard.ron";
+
 fn main() {
     println!("Hello, world!");
 
     env_logger::Builder::new()
         .filter_module("wgpu", log::LevelFilter::Warn)
         .filter_module("image_manager", log::LevelFilter::Trace)
+        .filter_module("ik_creatures", log::LevelFilter::Info)
         .format_timestamp(None)
         .init();
 
@@ -109,13 +122,26 @@ pub struct App {
     circles: CirclePipeline,
     text: TextPipeline,
     polygons: PolygonPipeline,
+    debug: DebugPipeline,
+    debug_draw: DebugDraw,
 
     camera: OrthographicCamera,
     mouse_pos: Vec2,
     mouse_vector: Vec2,
     mouse_down: bool,
-
-    ik: InverseKinematic,
+    show_limits: bool,
+    show_labels: bool,
+    self_collision: bool,
+    /// Collide with and place targets on `world`.
+    use_world: bool,
+    world: World,
+
+    creature: Creature,
+    #[cfg(feature = "serde")]
+    watcher: CreatureWatcher,
+
+    timestep: FixedTimestep,
+    previous_pose: Pose,
 }
 
 impl App {
@@ -126,20 +152,47 @@ impl App {
                 .unwrap(),
         );
 
-        let mut renderer = Renderer::new(window.clone()).block_on().unwrap();
+        let mut renderer =
+            Renderer::new_with_settings(window.clone(), RendererSettings::default().with_msaa(4))
+                .block_on()
+                .unwrap();
         let circles = renderer.create_pipeline();
         let text = renderer.create_pipeline();
         let polygons = renderer.create_pipeline();
+        let debug = renderer.create_pipeline();
 
         let camera = OrthographicCamera::default();
         renderer.update_camera(0, &camera);
 
-        let ik = InverseKinematic::new(Vec2::ZERO, Vec2::ZERO).with_nodes([
-            Node::default(),
-            Node::default(),
-            Node::default(),
-            Node::default(),
-        ]);
+        #[cfg(feature = "serde")]
+        let (creature, watcher) = {
+            let path = std::env::args()
+                .nth(1)
+                .unwrap_or_else(|| DEFAULT_CREATURE.into());
+            let mut watcher = CreatureWatcher::new(path);
+
+            let creature = match watcher.poll() {
+                Some(Ok(def)) => def.build(),
+                Some(Err(e)) => {
+                    log::error!("{:?}", e);
+                    fallback_creature().build()
+                }
+                None => fallback_creature().build(),
+            };
+
+            (creature, watcher)
+        };
+
+        // Creature files need the serde feature
+        #[cfg(not(feature = "serde"))]
+        let creature = {
+            log::warn!("Built without the serde feature, creature hot reload is unavailable");
+            if let Some(path) = std::env::args().nth(1) {
+                log::warn!("Ignoring creature path {}", path);
+            }
+
+            fallback_creature().build()
+        };
 
         Self {
             window,
@@ -147,11 +200,22 @@ impl App {
             circles,
             text,
             polygons,
+            debug,
+            debug_draw: DebugDraw::new(),
             camera,
             mouse_pos: Vec2::ZERO,
             mouse_vector: Vec2::ZERO,
             mouse_down: false,
-            ik,
+            show_limits: false,
+            show_labels: false,
+            self_collision: false,
+            use_world: false,
+            world: demo_world(),
+            previous_pose: creature.skeleton.pose(),
+            creature,
+            #[cfg(feature = "serde")]
+            watcher,
+            timestep: FixedTimestep::from_hz(60.),
         }
     }
 
@@ -189,12 +253,32 @@ impl App {
                 }
                 _ => {}
             },
+            winit::event::WindowEvent::KeyboardInput { event, .. }
+                if event.state.is_pressed() && !event.repeat =>
+            {
+                let key = |code| event.physical_key == winit::keyboard::PhysicalKey::Code(code);
+
+                if key(winit::keyboard::KeyCode::KeyL) {
+                    self.show_limits = !self.show_limits;
+                }
+                if key(winit::keyboard::KeyCode::KeyN) {
+                    self.show_labels = !self.show_labels;
+                }
+                if key(winit::keyboard::KeyCode::KeyC) {
+                    self.self_collision = !self.self_collision;
+                }
+                if key(winit::keyboard::KeyCode::KeyG) {
+                    self.use_world = !self.use_world;
+                }
+            }
 
             _ => {}
         }
     }
 
     fn resize(&mut self, size: winit::dpi::PhysicalSize<u32>) {
+        self.renderer.resize(size.width, size.height);
+
         let half_width = size.width as f32 / 2.;
         let half_height = size.height as f32 / 2.;
 
@@ -211,43 +295,73 @@ impl App {
     }
 
     fn tick(&mut self) {
-        // if self.mouse_down {
-        //     if let Some(mut root) = self.skeleton.get_node_mut(0) {
-        //         root.pos = self.mouse_pos;
-        //         root.set_rotation(self.mouse_vector.to_angle());
-        //     }
-        // }
+        #[cfg(feature = "serde")]
+        match self.watcher.poll() {
+            Some(Ok(def)) => {
+                log::info!("Reloaded creature {}", self.watcher.path().display());
+                self.creature.rebuild(&def);
+                self.previous_pose = self.creature.skeleton.pose();
+            }
+            Some(Err(e)) => log::error!("{:?}", e),
+            None => {}
+        }
 
-        // if let Some(ik) = self.skeleton.get_ik(0) {
-        //     ik.target = self.mouse_pos;
-        // }
+        (0..self.timestep.tick()).for_each(|_| self.update(self.timestep.step()));
 
-        self.ik.target = self.mouse_pos;
-        self.ik.fabrik();
+        // Draw between the last two simulation steps
+        let current_pose = self.creature.skeleton.pose();
+        self.creature.skeleton.set_pose(
+            &self
+                .previous_pose
+                .lerp(&current_pose, self.timestep.alpha()),
+        );
 
         self.renderer
-            .update_pipeline(&mut self.circles, self.ik.circles().as_slice());
+            .update_pipeline(&mut self.circles, self.creature.circles().as_slice());
+
+        let meshes = match self.creature.style.skin {
+            true => self
+                .creature
+                .skeleton
+                .skin_meshes(SkinSettings::default(), &self.creature.style.body_colors),
+            false => Vec::new(),
+        };
+        let meshes = meshes
+            .iter()
+            .map(|(vertices, indices)| (vertices.as_slice(), indices.as_slice()))
+            .collect::<Vec<_>>();
 
-        // self.skeleton.tick();
-
-        // let circle_instances = self.skeleton.circles();
+        self.renderer
+            .update_pipeline(&mut self.polygons, meshes.as_slice());
 
-        // self.renderer
-        //     .update_pipeline(&mut self.circles, circle_instances.as_slice());
+        if self.use_world {
+            self.world.debug_draw(&mut self.debug_draw);
+        }
+        if self.show_limits {
+            self.creature
+                .skeleton
+                .debug_draw_limits(&mut self.debug_draw);
+        }
+        self.creature.skeleton.debug_draw(&mut self.debug_draw);
+        self.renderer
+            .update_pipeline(&mut self.debug, &mut self.debug_draw);
 
-        // let mesh_nodes = self.skeleton.triangle_list();
-        // let (vertices, indices) = polygon::calculate_strip(&mesh_nodes[0]);
+        let labels = match self.show_labels {
+            true => self.labels(),
+            false => Vec::new(),
+        };
+        let text = self.text.project_labels(&self.camera, &labels);
+        self.renderer
+            .update_pipeline(&mut self.text, text.as_slice());
 
-        // self.renderer.update_pipeline(
-        //     &mut self.polygons,
-        //     &[(vertices.as_slice(), indices.as_slice())],
-        // );
+        self.creature.skeleton.set_pose(&current_pose);
 
         self.renderer
             .render(&mut [
                 // -
                 &mut self.polygons,
                 &mut self.circles,
+                &mut self.debug,
                 &mut self.text,
             ])
             .unwrap();
@@ -258,6 +372,127 @@ impl App {
 
         // std::thread::sleep(std::time::Duration::from_millis(2000));
     }
+
+    /// Advance the simulation by a single fixed step.
+    fn update(&mut self, dt: f32) {
+        let skeleton = &mut self.creature.skeleton;
+        self.previous_pose = skeleton.pose();
+
+        if self.mouse_down {
+            if let Some(root) = skeleton.get_node_mut(0) {
+                root.pos = self.mouse_pos;
+                root.set_rotation(self.mouse_vector.to_angle());
+            }
+        }
+
+        // Plant targets on the ground below the mouse when there is a world
+        let target = match self.use_world {
+            true => self
+                .world
+                .ground(self.mouse_pos, 0.)
+                .map_or(self.mouse_pos, |hit| hit.point),
+            false => self.mouse_pos,
+        };
+
+        let mut index = 0;
+        while let Some(ik) = skeleton.get_ik(index) {
+            ik.target = target;
+            index += 1;
+        }
+
+        if self.self_collision {
+            skeleton.collide(&CollisionSettings::default());
+        }
+        if self.use_world {
+            skeleton.collide_world(&self.world);
+        }
+
+        skeleton.update(dt);
+
+        if self.use_world {
+            skeleton.check_occlusion(&self.world);
+        }
+    }
+
+    /// Node indices and inverse kinematic solver results.
+    fn labels(&mut self) -> Vec<TextLabel> {
+        let skeleton = &mut self.creature.skeleton;
+
+        let mut labels = self
+            .creature
+            .nodes
+            .iter()
+            .enumerate()
+            .filter_map(|(index, id)| {
+                let node = skeleton.get_node(*id)?;
+                Some(TextLabel::new(index.to_string(), node.pos))
+            })
+            .collect::<Vec<_>>();
+
+        let mut index = 0;
+        while let Some(ik) = skeleton.get_ik(index) {
+            labels.push(
+                TextLabel::new(
+                    format!(
+                        "{} iterations, error {:.2}{}",
+                        ik.report.iterations,
+                        ik.report.error,
+                        match ik.report.occluded {
+                            true => ", occluded",
+                            false => "",
+                        }
+                    ),
+                    ik.target + vec2(0., 24.),
+                )
+                .with_size(14.),
+            );
+            index += 1;
+        }
+
+        labels
+    }
+}
+
+//====================================================================
+
+/// Rolling ground with a rock and a post to walk around.
+fn demo_world() -> World {
+    let heights = (0..=40)
+        .map(|index| -260. + (index as f32 * 0.5).sin() * 30.)
+        .collect();
+
+    World::new()
+        .with_shape(Shape::Heightfield(Heightfield::new(-1000., 50., heights)))
+        .with_shape(Shape::Polygon(vec![
+            vec2(150., -240.),
+            vec2(320., -240.),
+            vec2(280., -140.),
+            vec2(190., -120.),
+        ]))
+        .with_shape(Shape::Circle {
+            center: vec2(-250., 0.),
+            radius: 40.,
+        })
+}
+
+//====================================================================
+
+/// Arm following the mouse, used when no creature file could be loaded.
+fn fallback_creature() -> CreatureDef {
+    CreatureDef {
+        nodes: vec![NodeDef::default(); 4],
+        chains: vec![ChainDef::Inverse {
+            nodes: vec![0, 1, 2, 3],
+            target: None,
+            solver: Default::default(),
+            limits: Default::default(),
+            pole: None,
+            bend: None,
+            max_speed: None,
+            layers: Default::default(),
+        }],
+        ..Default::default()
+    }
 }
 
 //====================================================================
@@ -59,20 +59,26 @@ fn vs_main(in: VertexIn) -> VertexOut {
 fn fs_main(in: VertexOut) -> @location(0) vec4<f32> {
     let distance = distance(in.pos, in.center);
 
-    if distance < in.radius {
-        if in.color.w == 0. {
-            discard;
-        }
-        return in.color;
+    // Width of a pixel in world units, used to smooth the edges over one pixel
+    let edge = max(fwidth(distance), 0.0001) * 0.5;
+
+    let outer_radius = in.radius + in.border_radius;
+    let outer = 1. - smoothstep(outer_radius - edge, outer_radius + edge, distance);
+    let inner = 1. - smoothstep(in.radius - edge, in.radius + edge, distance);
+
+    var border_color = in.border_color;
+    if in.border_radius <= 0. {
+        border_color = in.color;
     }
-    if distance <= in.radius + in.border_radius {
-        if in.border_color.w == 0. {
-            discard;
-        }
-        return in.border_color;
+
+    var color = mix(border_color, in.color, inner);
+    color.a *= outer;
+
+    if color.a <= 0. {
+        discard;
     }
 
-    discard;
+    return color;
 }
 
 //====================================================================
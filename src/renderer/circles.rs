@@ -113,15 +113,15 @@ impl Pipeline for CirclePipeline {
             &[&unique.camera_bind_group_layout],
             &[RawVertex::desc(), RawInstance::desc()],
             include_str!("circle_shader.wgsl").into(),
-            // tools::RenderPipelineDescriptor {
-            //     fragment_targets: Some(&[Some(wgpu::ColorTargetState {
-            //         format: core.config.format,
-            //         blend: Some(wgpu::BlendState::ALPHA_BLENDING),
-            //         write_mask: wgpu::ColorWrites::all(),
-            //     })]),
-            //     ..Default::default()
-            // },
-            tools::RenderPipelineDescriptor::default(),
+            tools::RenderPipelineDescriptor {
+                fragment_targets: Some(&[Some(wgpu::ColorTargetState {
+                    format: core.config.format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::all(),
+                })]),
+                multisample: core.multisample(),
+                ..Default::default()
+            },
         );
 
         let vertex_buffer = core
@@ -0,0 +1,253 @@
+//====================================================================
+
+use std::f32::consts::TAU;
+
+use glam::{vec2, Vec2};
+
+use super::{
+    polygon::RawVertex,
+    tools::{self, Pipeline, PipelineUpdate, Vertex},
+    uniques::Uniques,
+    Core,
+};
+
+//====================================================================
+
+/// Immediate mode collector for debug shapes. Shapes are queued as triangles
+/// during a frame and cleared once handed to a [`DebugPipeline`].
+#[derive(Clone, Debug, Default)]
+pub struct DebugDraw {
+    vertices: Vec<RawVertex>,
+}
+
+impl DebugDraw {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn vertices(&self) -> &[RawVertex] {
+        &self.vertices
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+    }
+
+    //--------------------------------------------------
+
+    /// Filled triangle.
+    pub fn triangle(&mut self, a: Vec2, b: Vec2, c: Vec2, color: [f32; 4]) {
+        // Keep counter clockwise winding so back face culling keeps it
+        let (b, c) = match (b - a).perp_dot(c - a) < 0. {
+            true => (c, b),
+            false => (b, c),
+        };
+
+        self.vertices
+            .extend([a, b, c].map(|pos| RawVertex::new(pos.to_array()).with_color(color)));
+    }
+
+    fn quad(&mut self, a: Vec2, b: Vec2, c: Vec2, d: Vec2, color: [f32; 4]) {
+        self.triangle(a, b, c, color);
+        self.triangle(a, c, d, color);
+    }
+
+    pub fn line(&mut self, start: Vec2, end: Vec2, thickness: f32, color: [f32; 4]) {
+        let Some(direction) = (end - start).try_normalize() else {
+            return;
+        };
+        let side = direction.perp() * thickness / 2.;
+
+        self.quad(start - side, end - side, end + side, start + side, color);
+    }
+
+    /// Line with a triangular head at `end`.
+    pub fn arrow(&mut self, start: Vec2, end: Vec2, thickness: f32, color: [f32; 4]) {
+        let length = start.distance(end);
+        let Some(direction) = (end - start).try_normalize() else {
+            return;
+        };
+
+        let head_length = (thickness * 4.).min(length / 2.);
+        let head_base = end - direction * head_length;
+        let head_side = direction.perp() * head_length * 0.6;
+
+        self.line(start, head_base, thickness, color);
+        self.triangle(end, head_base + head_side, head_base - head_side, color);
+    }
+
+    /// Arc starting at `start_angle` and sweeping counter clockwise by `sweep` radians.
+    pub fn arc(
+        &mut self,
+        center: Vec2,
+        radius: f32,
+        start_angle: f32,
+        sweep: f32,
+        thickness: f32,
+        color: [f32; 4],
+    ) {
+        let inner = (radius - thickness / 2.).max(0.);
+        let outer = radius + thickness / 2.;
+
+        let points = arc_points(start_angle, sweep, radius);
+        points.windows(2).for_each(|pair| {
+            self.quad(
+                center + pair[0] * inner,
+                center + pair[0] * outer,
+                center + pair[1] * outer,
+                center + pair[1] * inner,
+                color,
+            );
+        });
+    }
+
+    pub fn circle(&mut self, center: Vec2, radius: f32, thickness: f32, color: [f32; 4]) {
+        self.arc(center, radius, 0., TAU, thickness, color);
+    }
+
+    /// Filled pie slice starting at `start_angle` and sweeping by `sweep` radians.
+    pub fn wedge(
+        &mut self,
+        center: Vec2,
+        radius: f32,
+        start_angle: f32,
+        sweep: f32,
+        color: [f32; 4],
+    ) {
+        let points = arc_points(start_angle, sweep, radius);
+        points.windows(2).for_each(|pair| {
+            self.triangle(
+                center,
+                center + pair[0] * radius,
+                center + pair[1] * radius,
+                color,
+            );
+        });
+    }
+
+    /// Diagonal cross centred on a point.
+    pub fn cross(&mut self, center: Vec2, size: f32, thickness: f32, color: [f32; 4]) {
+        let offset = Vec2::splat(size / 2.);
+        let flipped = vec2(offset.x, -offset.y);
+
+        self.line(center - offset, center + offset, thickness, color);
+        self.line(center - flipped, center + flipped, thickness, color);
+    }
+
+    /// Outline of an axis aligned rectangle.
+    pub fn rect(&mut self, min: Vec2, max: Vec2, thickness: f32, color: [f32; 4]) {
+        let (min, max) = (min.min(max), min.max(max));
+        let half = thickness / 2.;
+
+        // Extend horizontal edges over the corners so they are filled
+        self.line(
+            vec2(min.x - half, min.y),
+            vec2(max.x + half, min.y),
+            thickness,
+            color,
+        );
+        self.line(
+            vec2(min.x - half, max.y),
+            vec2(max.x + half, max.y),
+            thickness,
+            color,
+        );
+        self.line(vec2(min.x, min.y), vec2(min.x, max.y), thickness, color);
+        self.line(vec2(max.x, min.y), vec2(max.x, max.y), thickness, color);
+    }
+}
+
+/// Unit directions along an arc, with enough segments to look round at `radius`.
+fn arc_points(start_angle: f32, sweep: f32, radius: f32) -> Vec<Vec2> {
+    let segments = ((sweep.abs() * radius.max(1.).sqrt() * 1.5).ceil() as usize).clamp(2, 128);
+
+    (0..=segments)
+        .map(|index| Vec2::from_angle(start_angle + sweep * index as f32 / segments as f32))
+        .collect()
+}
+
+//====================================================================
+
+pub struct DebugPipeline {
+    pipeline: wgpu::RenderPipeline,
+
+    vertex_buffer: wgpu::Buffer,
+    vertex_capacity: u32,
+    vertex_count: u32,
+}
+
+impl Pipeline for DebugPipeline {
+    fn new(core: &Core, uniques: &mut Uniques) -> Self {
+        let unique = uniques.first(core.device());
+
+        let pipeline = tools::create_pipeline(
+            core.device(),
+            core.config(),
+            "Debug Pipeline",
+            &[&unique.camera_bind_group_layout],
+            &[RawVertex::desc()],
+            include_str!("polygon_shader.wgsl"),
+            tools::RenderPipelineDescriptor {
+                fragment_targets: Some(&[Some(wgpu::ColorTargetState {
+                    format: core.config().format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::all(),
+                })]),
+                multisample: core.multisample(),
+                ..Default::default()
+            },
+        );
+
+        let vertex_buffer = core.device().create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Debug Pipeline Instance Buffer"),
+            size: 0,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            pipeline,
+            vertex_buffer,
+            vertex_capacity: 0,
+            vertex_count: 0,
+        }
+    }
+
+    fn render(&mut self, pass: &mut wgpu::RenderPass, uniques: &Uniques) {
+        if self.vertex_count == 0 {
+            return;
+        }
+
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &uniques.get(0).unwrap().camera_bind_group, &[]);
+
+        pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        pass.draw(0..self.vertex_count, 0..1);
+    }
+}
+
+/// Upload everything queued this frame and clear the queue.
+impl PipelineUpdate<&mut DebugDraw> for DebugPipeline {
+    fn update(&mut self, core: &Core, data: &mut DebugDraw) {
+        tools::update_instance_buffer(
+            core.device(),
+            core.queue(),
+            "Debug Pipeline",
+            &mut self.vertex_buffer,
+            &mut self.vertex_capacity,
+            data.vertices(),
+        );
+
+        self.vertex_count = data.vertices.len() as u32;
+        data.clear();
+    }
+}
+
+//====================================================================
@@ -3,21 +3,46 @@
 use std::sync::Arc;
 
 use anyhow::Context;
+use software::Image;
 use tools::{Pipeline, PipelineUpdate};
 use uniques::{Camera, Uniques};
 
 pub mod circles;
+pub mod debug;
 pub mod polygon;
+pub mod software;
+pub mod svg;
 pub mod text;
 pub mod tools;
 pub mod uniques;
 
 //====================================================================
 
+#[derive(Clone, Copy, Debug)]
+pub struct RendererSettings {
+    /// Samples per pixel for multisample anti-aliasing. 1 disables MSAA.
+    pub msaa_samples: u32,
+}
+
+impl Default for RendererSettings {
+    fn default() -> Self {
+        Self { msaa_samples: 1 }
+    }
+}
+
+impl RendererSettings {
+    pub fn with_msaa(mut self, samples: u32) -> Self {
+        self.msaa_samples = samples;
+        self
+    }
+}
+
 pub struct Renderer {
     core: Core,
 
     uniques: Uniques,
+    clear_color: wgpu::Color,
+    msaa_view: Option<wgpu::TextureView>,
 }
 
 pub struct Core {
@@ -25,6 +50,7 @@ pub struct Core {
     queue: wgpu::Queue,
     surface: wgpu::Surface<'static>,
     config: wgpu::SurfaceConfiguration,
+    sample_count: u32,
 }
 impl Core {
     #[inline]
@@ -39,10 +65,49 @@ impl Core {
     pub fn config(&self) -> &wgpu::SurfaceConfiguration {
         &self.config
     }
+    /// Multisample state every pipeline has to be created with.
+    #[inline]
+    pub fn multisample(&self) -> wgpu::MultisampleState {
+        wgpu::MultisampleState {
+            count: self.sample_count,
+            ..Default::default()
+        }
+    }
+
+    fn create_msaa_view(&self, width: u32, height: u32) -> Option<wgpu::TextureView> {
+        if self.sample_count <= 1 {
+            return None;
+        }
+
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Multisample Texture"),
+            size: wgpu::Extent3d {
+                width: width.max(1),
+                height: height.max(1),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: self.sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.config.format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+
+        Some(texture.create_view(&wgpu::TextureViewDescriptor::default()))
+    }
 }
 
 impl Renderer {
+    #[inline]
     pub async fn new(window: Arc<winit::window::Window>) -> anyhow::Result<Self> {
+        Self::new_with_settings(window, RendererSettings::default()).await
+    }
+
+    pub async fn new_with_settings(
+        window: Arc<winit::window::Window>,
+        settings: RendererSettings,
+    ) -> anyhow::Result<Self> {
         let size = window.inner_size();
 
         let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
@@ -84,19 +149,55 @@ impl Renderer {
 
         surface.configure(&device, &config);
 
+        let sample_count = match adapter
+            .get_texture_format_features(config.format)
+            .flags
+            .sample_count_supported(settings.msaa_samples)
+        {
+            true => settings.msaa_samples.max(1),
+            false => {
+                log::warn!(
+                    "{}x MSAA is not supported for {:?}, disabling it",
+                    settings.msaa_samples,
+                    config.format
+                );
+                1
+            }
+        };
+
+        let core = Core {
+            device,
+            queue,
+            surface,
+            config,
+            sample_count,
+        };
+        let msaa_view = core.create_msaa_view(size.width, size.height);
+
         let uniques = Uniques::default();
 
         Ok(Self {
-            core: Core {
-                device,
-                queue,
-                surface,
-                config,
-            },
+            core,
             uniques,
+            clear_color: wgpu::Color::WHITE,
+            msaa_view,
         })
     }
 
+    pub fn resize(&mut self, width: u32, height: u32) {
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        self.core.config.width = width;
+        self.core.config.height = height;
+        self.core
+            .surface
+            .configure(&self.core.device, &self.core.config);
+
+        self.msaa_view = self.core.create_msaa_view(width, height);
+    }
+
     #[inline]
     pub fn create_pipeline<T: Pipeline>(&mut self) -> T {
         T::new(&self.core, &mut self.uniques)
@@ -117,6 +218,16 @@ impl Renderer {
         &self.uniques
     }
 
+    #[inline]
+    pub fn clear_color(&self) -> wgpu::Color {
+        self.clear_color
+    }
+
+    #[inline]
+    pub fn set_clear_color(&mut self, color: wgpu::Color) {
+        self.clear_color = color;
+    }
+
     #[inline]
     pub fn update_camera(&mut self, slot: usize, data: &dyn Camera) {
         self.uniques.update_camera(&self.core.queue, slot, data);
@@ -133,32 +244,175 @@ impl Renderer {
             .device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
 
-        {
-            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("Render Pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &surface_view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color::WHITE),
-                        store: wgpu::StoreOp::Store,
-                    },
-                })],
-                depth_stencil_attachment: None,
-                timestamp_writes: None,
-                occlusion_query_set: None,
-            });
-
-            pipelines.into_iter().for_each(|pipeline| {
-                pipeline.render(&mut pass, &self.uniques);
-            });
-        }
+        self.render_pass(
+            &mut encoder,
+            &surface_view,
+            self.msaa_view.as_ref(),
+            self.clear_color,
+            pipelines,
+        );
 
         self.core.queue.submit(Some(encoder.finish()));
         surface_texture.present();
 
         Ok(())
     }
+
+    /// Render a frame into an offscreen texture instead of the window surface and
+    /// read it back into an [`Image`]. Pipelines with size dependent state (such as
+    /// text) should be resized to match beforehand.
+    pub fn render_to_image(
+        &self,
+        pipelines: &mut [&mut dyn Pipeline],
+        width: u32,
+        height: u32,
+        clear_color: wgpu::Color,
+    ) -> anyhow::Result<Image> {
+        anyhow::ensure!(
+            width > 0 && height > 0,
+            "Cannot render to a {}x{} image",
+            width,
+            height
+        );
+
+        let format = self.core.config.format;
+        let bytes_per_pixel = format
+            .block_copy_size(None)
+            .filter(|size| *size == 4)
+            .with_context(|| format!("Unsupported offscreen texture format {:?}", format))?;
+
+        let texture = self.core.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Offscreen Texture"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        // Buffer rows have to be aligned when copying out of a texture
+        let unpadded_bytes_per_row = width * bytes_per_pixel;
+        let padded_bytes_per_row = unpadded_bytes_per_row
+            .div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
+            * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+
+        let buffer = self.core.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Offscreen Readback Buffer"),
+            size: padded_bytes_per_row as u64 * height as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self
+            .core
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+
+        let msaa_view = self.core.create_msaa_view(width, height);
+        self.render_pass(
+            &mut encoder,
+            &view,
+            msaa_view.as_ref(),
+            clear_color,
+            pipelines,
+        );
+
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            texture.size(),
+        );
+
+        self.core.queue.submit(Some(encoder.finish()));
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let slice = buffer.slice(..);
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            sender.send(result).ok();
+        });
+        self.core.device.poll(wgpu::Maintain::Wait);
+
+        receiver
+            .recv()
+            .context("Offscreen readback buffer was never mapped")??;
+
+        let mut image = Image::new(width, height);
+        {
+            let data = slice.get_mapped_range();
+
+            data.chunks_exact(padded_bytes_per_row as usize)
+                .zip(
+                    image
+                        .pixels
+                        .chunks_exact_mut(unpadded_bytes_per_row as usize),
+                )
+                .for_each(|(padded, row)| {
+                    row.copy_from_slice(&padded[..unpadded_bytes_per_row as usize])
+                });
+        }
+        buffer.unmap();
+
+        if matches!(
+            format,
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+        ) {
+            image
+                .pixels
+                .chunks_exact_mut(4)
+                .for_each(|pixel| pixel.swap(0, 2));
+        }
+
+        Ok(image)
+    }
+
+    /// Render into `view`, drawing into `msaa_view` and resolving into `view` when
+    /// multisampling.
+    fn render_pass(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+        msaa_view: Option<&wgpu::TextureView>,
+        clear_color: wgpu::Color,
+        pipelines: &mut [&mut dyn Pipeline],
+    ) {
+        let (view, resolve_target) = match msaa_view {
+            Some(msaa_view) => (msaa_view, Some(view)),
+            None => (view, None),
+        };
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Render Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(clear_color),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        pipelines.into_iter().for_each(|pipeline| {
+            pipeline.render(&mut pass, &self.uniques);
+        });
+    }
 }
 
 //====================================================================
@@ -9,15 +9,19 @@ use super::tools::{Pipeline, PipelineUpdate, Vertex};
 //====================================================================
 
 #[repr(C)]
-#[derive(bytemuck::Pod, bytemuck::Zeroable, Clone, Copy)]
+#[derive(bytemuck::Pod, bytemuck::Zeroable, Clone, Copy, Debug, PartialEq)]
 pub struct RawVertex {
-    pos: [f32; 2],
+    pub pos: [f32; 2],
+    pub color: [f32; 4],
+    pub uv: [f32; 2],
 }
 
 impl Vertex for RawVertex {
     fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
-        const VERTEX_ATTRIBUTES: [wgpu::VertexAttribute; 1] = wgpu::vertex_attr_array![
-            0 => Float32x2
+        const VERTEX_ATTRIBUTES: [wgpu::VertexAttribute; 3] = wgpu::vertex_attr_array![
+            0 => Float32x2,
+            1 => Float32x4,
+            2 => Float32x2,
         ];
 
         wgpu::VertexBufferLayout {
@@ -28,6 +32,127 @@ impl Vertex for RawVertex {
     }
 }
 
+impl RawVertex {
+    pub fn new(pos: [f32; 2]) -> Self {
+        Self {
+            pos,
+            color: [1., 0., 0., 1.],
+            uv: [0., 0.],
+        }
+    }
+    pub fn with_color(mut self, color: [f32; 4]) -> Self {
+        self.color = color;
+        self
+    }
+    pub fn with_uv(mut self, uv: [f32; 2]) -> Self {
+        self.uv = uv;
+        self
+    }
+}
+
+//====================================================================
+
+/// Colours for body meshes, picked by position along and across the body.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BodyColors {
+    /// Colour at the first node, blended towards `tail` along the body.
+    pub head: [f32; 4],
+    pub tail: [f32; 4],
+    /// Colour of the left edge of the body, blended towards the centre line.
+    pub left: Option<[f32; 4]>,
+    /// Colour of the right edge of the body, blended towards the centre line.
+    pub right: Option<[f32; 4]>,
+    pub stripes: Option<Stripes>,
+}
+
+/// Bands of colour repeating along the body.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stripes {
+    pub color: [f32; 4],
+    /// Number of nodes in each band.
+    pub width: f32,
+}
+
+impl Default for BodyColors {
+    fn default() -> Self {
+        Self {
+            head: [1., 0., 0., 1.],
+            tail: [1., 0., 0., 1.],
+            left: None,
+            right: None,
+            stripes: None,
+        }
+    }
+}
+
+impl BodyColors {
+    pub fn solid(color: [f32; 4]) -> Self {
+        Self {
+            head: color,
+            tail: color,
+            ..Default::default()
+        }
+    }
+
+    pub fn gradient(head: [f32; 4], tail: [f32; 4]) -> Self {
+        Self {
+            head,
+            tail,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_sides(mut self, left: [f32; 4], right: [f32; 4]) -> Self {
+        self.left = Some(left);
+        self.right = Some(right);
+        self
+    }
+
+    pub fn with_stripes(mut self, color: [f32; 4], width: f32) -> Self {
+        self.stripes = Some(Stripes { color, width });
+        self
+    }
+
+    /// Vertex for a point of a body. `node` is the (fractional) index of the node
+    /// the point belongs to and `side` runs from -1 on the right edge, through 0 on
+    /// the centre line, to 1 on the left edge.
+    pub fn vertex(&self, pos: [f32; 2], node: f32, node_count: usize, side: f32) -> RawVertex {
+        let along = match node_count > 1 {
+            true => (node / (node_count - 1) as f32).clamp(0., 1.),
+            false => 0.,
+        };
+
+        RawVertex::new(pos)
+            .with_color(self.color(node, along, side))
+            .with_uv([along, (side + 1.) / 2.])
+    }
+
+    fn color(&self, node: f32, along: f32, side: f32) -> [f32; 4] {
+        let mut color = lerp(self.head, self.tail, along);
+
+        if let Some(stripes) = self.stripes {
+            if stripes.width > 0. && (node / stripes.width).floor() as i32 % 2 == 1 {
+                color = stripes.color;
+            }
+        }
+
+        match (side > 0., self.left, self.right) {
+            (true, Some(left), _) => lerp(color, left, side.min(1.)),
+            (false, _, Some(right)) => lerp(color, right, (-side).min(1.)),
+            _ => color,
+        }
+    }
+}
+
+fn lerp(from: [f32; 4], to: [f32; 4], amount: f32) -> [f32; 4] {
+    std::array::from_fn(|index| from[index] + (to[index] - from[index]) * amount)
+}
+
+//====================================================================
+
 pub struct PolygonPipeline {
     pipeline: wgpu::RenderPipeline,
 
@@ -55,7 +180,15 @@ impl Pipeline for PolygonPipeline {
             &[&unique.camera_bind_group_layout],
             &[RawVertex::desc()],
             include_str!("polygon_shader.wgsl").into(),
-            tools::RenderPipelineDescriptor::default(),
+            tools::RenderPipelineDescriptor {
+                fragment_targets: Some(&[Some(wgpu::ColorTargetState {
+                    format: core.config().format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::all(),
+                })]),
+                multisample: core.multisample(),
+                ..Default::default()
+            },
         );
 
         Self {
@@ -159,14 +292,32 @@ impl PipelineUpdate<&[(&[RawVertex], &[u16])]> for PolygonPipeline {
 //====================================================================
 
 pub fn calculate_strip(vertices: &[[f32; 2]]) -> (Vec<RawVertex>, Vec<u16>) {
+    calculate_strip_colored(vertices, &BodyColors::default())
+}
+
+/// Same as [`calculate_strip`], colouring the vertices by the node they belong to.
+/// Vertices are expected in right/left pairs per node as produced by `triangle_list`.
+pub fn calculate_strip_colored(
+    vertices: &[[f32; 2]],
+    colors: &BodyColors,
+) -> (Vec<RawVertex>, Vec<u16>) {
     if vertices.len() < 4 {
         return (Vec::new(), Vec::new());
     }
 
-    let vertices = vertices.into_iter().fold(Vec::new(), |mut acc, vertex| {
-        acc.push(RawVertex { pos: *vertex });
-        acc
-    });
+    let node_count = vertices.len() / 2;
+
+    let vertices = vertices
+        .iter()
+        .enumerate()
+        .fold(Vec::new(), |mut acc, (index, vertex)| {
+            let side = match index % 2 {
+                0 => -1.,
+                _ => 1.,
+            };
+            acc.push(colors.vertex(*vertex, (index / 2) as f32, node_count, side));
+            acc
+        });
 
     let indices = (3..vertices.len())
         .step_by(2)
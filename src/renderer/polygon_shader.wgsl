@@ -13,11 +13,14 @@ struct Camera {
 struct VertexIn {
     // Vertex
     @location(0) vertex_pos: vec2<f32>,
+    @location(1) color: vec4<f32>,
+    @location(2) uv: vec2<f32>,
 }
 
 struct VertexOut {
     @builtin(position) clip_position: vec4<f32>,
-    // @location(0) color: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) uv: vec2<f32>,
 }
 
 //====================================================================
@@ -30,14 +33,15 @@ fn vs_main(in: VertexIn) -> VertexOut {
         camera.projection * 
         vec4<f32>(in.vertex_pos, 0., 1.);
 
-    // out.color = in.color;
+    out.color = in.color;
+    out.uv = in.uv;
 
     return out;
 }
 
 @fragment
 fn fs_main(in: VertexOut) -> @location(0) vec4<f32> {
-    return vec4<f32>(1., 0., 0., 1.);
+    return in.color;
 }
 
 //====================================================================
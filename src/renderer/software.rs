@@ -0,0 +1,457 @@
+//====================================================================
+
+use std::path::Path;
+
+use anyhow::Context;
+use glam::{vec2, vec4, Mat4, Vec2};
+
+use super::{circles::RawInstance, debug::DebugDraw, polygon::RawVertex, uniques::Camera};
+
+//====================================================================
+
+/// RGBA image with 8 bit sRGB channels, rows running from top to bottom.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0; width as usize * height as usize * 4],
+        }
+    }
+
+    pub fn get_pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        let index = (y as usize * self.width as usize + x as usize) * 4;
+        Some(self.pixels[index..index + 4].try_into().unwrap())
+    }
+
+    /// Encode the image as an 8 bit RGBA PNG.
+    pub fn to_png(&self) -> anyhow::Result<Vec<u8>> {
+        let mut data = Vec::new();
+
+        let mut encoder = png::Encoder::new(&mut data, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_source_srgb(png::SrgbRenderingIntent::Perceptual);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels)?;
+        writer.finish()?;
+
+        Ok(data)
+    }
+
+    pub fn save_png(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+
+        std::fs::write(path, self.to_png()?)
+            .with_context(|| format!("Failed to write png to {}", path.display()))
+    }
+
+    #[inline]
+    fn set_pixel(&mut self, x: u32, y: u32, color: [u8; 4]) {
+        let index = (y as usize * self.width as usize + x as usize) * 4;
+        self.pixels[index..index + 4].copy_from_slice(&color);
+    }
+
+    /// Blend a linear colour over a pixel the way `BlendState::ALPHA_BLENDING` does
+    /// on an sRGB target.
+    fn blend_pixel(&mut self, x: u32, y: u32, color: [f32; 4]) {
+        let index = (y as usize * self.width as usize + x as usize) * 4;
+        let dst = from_srgba8(self.pixels[index..index + 4].try_into().unwrap());
+
+        let alpha = color[3].clamp(0., 1.);
+        let blended = [
+            color[0] * alpha + dst[0] * (1. - alpha),
+            color[1] * alpha + dst[1] * (1. - alpha),
+            color[2] * alpha + dst[2] * (1. - alpha),
+            alpha + dst[3] * (1. - alpha),
+        ];
+
+        self.set_pixel(x, y, to_srgba8(blended));
+    }
+
+    fn fill(&mut self, color: [u8; 4]) {
+        self.pixels
+            .chunks_exact_mut(4)
+            .for_each(|pixel| pixel.copy_from_slice(&color));
+    }
+}
+
+//====================================================================
+
+/// CPU rasterizer drawing the same data as the circle and polygon pipelines into
+/// an [`Image`], for use without a window or GPU.
+pub struct SoftwareRenderer {
+    image: Image,
+    view_projection: Mat4,
+    inverse_view_projection: Mat4,
+}
+
+impl SoftwareRenderer {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            image: Image::new(width, height),
+            view_projection: Mat4::IDENTITY,
+            inverse_view_projection: Mat4::IDENTITY,
+        }
+    }
+
+    #[inline]
+    pub fn image(&self) -> &Image {
+        &self.image
+    }
+
+    #[inline]
+    pub fn into_image(self) -> Image {
+        self.image
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.image = Image::new(width, height);
+    }
+
+    pub fn update_camera(&mut self, camera: &dyn Camera) {
+        self.view_projection = camera.into_uniform().view_projection();
+        self.inverse_view_projection = self.view_projection.inverse();
+    }
+
+    pub fn clear(&mut self, color: [f32; 4]) {
+        self.image.fill(to_srgba8(color));
+    }
+
+    pub fn draw_circles(&mut self, instances: &[RawInstance]) {
+        instances
+            .iter()
+            .for_each(|instance| self.draw_circle(instance));
+    }
+
+    fn draw_circle(&mut self, instance: &RawInstance) {
+        let center = Vec2::from(instance.pos);
+        let half_size = instance.radius + instance.border_radius;
+
+        let Some((min, max)) = self.pixel_bounds(&[
+            center + vec2(-half_size, -half_size),
+            center + vec2(half_size, -half_size),
+            center + vec2(-half_size, half_size),
+            center + vec2(half_size, half_size),
+        ]) else {
+            return;
+        };
+
+        let border_color = match instance.border_radius <= 0. {
+            true => instance.color,
+            false => instance.border_color,
+        };
+        let outer_radius = instance.radius + instance.border_radius;
+
+        // World units covered by a single pixel along each axis
+        let origin = self.pixel_to_world(min.0, min.1);
+        let pixel_size = vec2(
+            (self.pixel_to_world(min.0 + 1, min.1) - origin).length(),
+            (self.pixel_to_world(min.0, min.1 + 1) - origin).length(),
+        );
+
+        (min.1..max.1).for_each(|y| {
+            (min.0..max.0).for_each(|x| {
+                let pos = self.pixel_to_world(x, y);
+                let distance = pos.distance(center);
+
+                // Same edge smoothing as the circle shader, approximating fwidth
+                let direction = (pos - center).normalize_or_zero().abs();
+                let edge = (direction.dot(pixel_size)).max(0.0001) * 0.5;
+
+                let outer = 1. - smoothstep(outer_radius - edge, outer_radius + edge, distance);
+                let inner =
+                    1. - smoothstep(instance.radius - edge, instance.radius + edge, distance);
+
+                let mut color: [f32; 4] = std::array::from_fn(|channel| {
+                    border_color[channel]
+                        + (instance.color[channel] - border_color[channel]) * inner
+                });
+                color[3] *= outer;
+
+                if color[3] > 0. {
+                    self.image.blend_pixel(x, y, color);
+                }
+            })
+        });
+    }
+
+    /// Draw an indexed triangle list such as one produced by `polygon::calculate_strip`.
+    pub fn draw_polygon(&mut self, vertices: &[RawVertex], indices: &[u16]) {
+        indices.chunks_exact(3).for_each(|triangle| {
+            let Some(points) = triangle
+                .iter()
+                .map(|index| vertices.get(*index as usize))
+                .collect::<Option<Vec<_>>>()
+            else {
+                return;
+            };
+
+            let ndc = points
+                .iter()
+                .map(|vertex| self.world_to_ndc(Vec2::from(vertex.pos)))
+                .collect::<Vec<_>>();
+
+            // Pipelines cull back faces with counter clockwise front faces
+            let area = (ndc[1] - ndc[0]).perp_dot(ndc[2] - ndc[0]);
+            if area <= 0. {
+                return;
+            }
+
+            let pixels = ndc
+                .iter()
+                .map(|ndc| self.ndc_to_pixel(*ndc))
+                .collect::<Vec<_>>();
+
+            let Some((min, max)) = self.clamp_bounds(
+                pixels.iter().copied().reduce(Vec2::min).unwrap(),
+                pixels.iter().copied().reduce(Vec2::max).unwrap(),
+            ) else {
+                return;
+            };
+
+            // Pixel space flips y so front faces wind clockwise
+            let edge = |a: Vec2, b: Vec2, point: Vec2| (b - a).perp_dot(point - a);
+
+            (min.1..max.1).for_each(|y| {
+                (min.0..max.0).for_each(|x| {
+                    let point = vec2(x as f32 + 0.5, y as f32 + 0.5);
+
+                    let weights = [
+                        edge(pixels[1], pixels[2], point),
+                        edge(pixels[2], pixels[0], point),
+                        edge(pixels[0], pixels[1], point),
+                    ];
+
+                    if weights.iter().any(|weight| *weight > 0.) {
+                        return;
+                    }
+
+                    // Interpolate vertex colours like the shader does
+                    let total = weights.iter().sum::<f32>();
+                    let color = std::array::from_fn(|channel| {
+                        points
+                            .iter()
+                            .zip(weights)
+                            .map(|(vertex, weight)| vertex.color[channel] * weight / total)
+                            .sum::<f32>()
+                    });
+
+                    self.image.blend_pixel(x, y, color);
+                })
+            });
+        });
+    }
+
+    pub fn draw_debug(&mut self, draw: &DebugDraw) {
+        // Vertices are unindexed triangles, draw them in chunks u16 indices can address
+        draw.vertices()
+            .chunks(u16::MAX as usize)
+            .for_each(|vertices| {
+                let indices = (0..vertices.len() as u16).collect::<Vec<_>>();
+                self.draw_polygon(vertices, &indices);
+            });
+    }
+
+    pub fn draw_polygons(&mut self, data: &[(&[RawVertex], &[u16])]) {
+        data.iter()
+            .for_each(|(vertices, indices)| self.draw_polygon(vertices, indices));
+    }
+
+    //--------------------------------------------------
+
+    fn world_to_ndc(&self, pos: Vec2) -> Vec2 {
+        let clip = self.view_projection * vec4(pos.x, pos.y, 0., 1.);
+        vec2(clip.x, clip.y) / clip.w
+    }
+
+    fn ndc_to_pixel(&self, ndc: Vec2) -> Vec2 {
+        vec2(
+            (ndc.x + 1.) / 2. * self.image.width as f32,
+            (1. - ndc.y) / 2. * self.image.height as f32,
+        )
+    }
+
+    /// World position at the centre of a pixel.
+    fn pixel_to_world(&self, x: u32, y: u32) -> Vec2 {
+        let ndc = vec2(
+            (x as f32 + 0.5) / self.image.width as f32 * 2. - 1.,
+            1. - (y as f32 + 0.5) / self.image.height as f32 * 2.,
+        );
+
+        let world = self.inverse_view_projection * vec4(ndc.x, ndc.y, 0., 1.);
+        vec2(world.x, world.y) / world.w
+    }
+
+    fn pixel_bounds(&self, points: &[Vec2]) -> Option<((u32, u32), (u32, u32))> {
+        let pixels = points
+            .iter()
+            .map(|point| self.ndc_to_pixel(self.world_to_ndc(*point)))
+            .collect::<Vec<_>>();
+
+        self.clamp_bounds(
+            pixels.iter().copied().reduce(Vec2::min)?,
+            pixels.iter().copied().reduce(Vec2::max)?,
+        )
+    }
+
+    fn clamp_bounds(&self, min: Vec2, max: Vec2) -> Option<((u32, u32), (u32, u32))> {
+        let size = vec2(self.image.width as f32, self.image.height as f32);
+
+        let min = min.floor().clamp(Vec2::ZERO, size);
+        let max = max.ceil().clamp(Vec2::ZERO, size);
+
+        if min.x >= max.x || min.y >= max.y {
+            return None;
+        }
+
+        Some(((min.x as u32, min.y as u32), (max.x as u32, max.y as u32)))
+    }
+}
+
+//====================================================================
+
+fn smoothstep(edge0: f32, edge1: f32, value: f32) -> f32 {
+    let t = ((value - edge0) / (edge1 - edge0)).clamp(0., 1.);
+    t * t * (3. - 2. * t)
+}
+
+/// Convert 8 bit sRGB back to a linear colour.
+fn from_srgba8(color: [u8; 4]) -> [f32; 4] {
+    let decode = |channel: u8| {
+        let channel = channel as f32 / 255.;
+        match channel <= 0.04045 {
+            true => channel / 12.92,
+            false => ((channel + 0.055) / 1.055).powf(2.4),
+        }
+    };
+
+    [
+        decode(color[0]),
+        decode(color[1]),
+        decode(color[2]),
+        color[3] as f32 / 255.,
+    ]
+}
+
+/// Convert a linear colour to 8 bit sRGB, matching what the sRGB surface stores.
+pub(super) fn to_srgba8(color: [f32; 4]) -> [u8; 4] {
+    let encode = |channel: f32| {
+        let channel = channel.clamp(0., 1.);
+        let srgb = match channel <= 0.0031308 {
+            true => channel * 12.92,
+            false => 1.055 * channel.powf(1. / 2.4) - 0.055,
+        };
+        (srgb * 255.).round() as u8
+    };
+
+    [
+        encode(color[0]),
+        encode(color[1]),
+        encode(color[2]),
+        (color[3].clamp(0., 1.) * 255.).round() as u8,
+    ]
+}
+
+//====================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::renderer::uniques::OrthographicCamera;
+
+    use super::*;
+
+    const BLACK: [u8; 4] = [0, 0, 0, 255];
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const GREEN: [u8; 4] = [0, 255, 0, 255];
+
+    /// 100 by 100 pixels with one world unit per pixel and the origin bottom left.
+    fn renderer() -> SoftwareRenderer {
+        let mut renderer = SoftwareRenderer::new(100, 100);
+        renderer.update_camera(&OrthographicCamera::new_sized(100., 100.));
+        renderer.clear([0., 0., 0., 1.]);
+        renderer
+    }
+
+    fn triangle(points: [[f32; 2]; 3]) -> Vec<RawVertex> {
+        points
+            .map(|pos| RawVertex::new(pos).with_color([0., 1., 0., 1.]))
+            .to_vec()
+    }
+
+    #[test]
+    fn circle_fills_center_and_fades_at_edge() {
+        // Radius off the pixel grid so the edge pixel is partly covered
+        let mut renderer = renderer();
+        renderer.draw_circles(&[RawInstance::new([50., 50.], 20.3)
+            .with_color([1., 0., 0., 1.])
+            .with_border(0., [0., 0., 0., 1.])]);
+        let image = renderer.image();
+
+        assert_eq!(image.get_pixel(50, 50), Some(RED));
+        assert_eq!(image.get_pixel(50, 35), Some(RED));
+        assert_eq!(image.get_pixel(5, 5), Some(BLACK));
+        assert_eq!(image.get_pixel(50, 90), Some(BLACK));
+
+        // Anti-aliased edge sits between the fill and the background
+        let edge = image.get_pixel(70, 50).unwrap();
+        assert!(edge[0] > 0 && edge[0] < 255, "{edge:?}");
+    }
+
+    #[test]
+    fn circle_border_surrounds_fill() {
+        let mut renderer = renderer();
+        renderer.draw_circles(&[RawInstance::new([50., 50.], 20.)
+            .with_color([1., 0., 0., 1.])
+            .with_border(10., [0., 1., 0., 1.])]);
+        let image = renderer.image();
+
+        assert_eq!(image.get_pixel(50, 50), Some(RED));
+        assert_eq!(image.get_pixel(75, 50), Some(GREEN));
+        assert_eq!(image.get_pixel(95, 50), Some(BLACK));
+    }
+
+    #[test]
+    fn polygon_fills_front_faces_only() {
+        let mut renderer = renderer();
+        renderer.draw_polygon(&triangle([[0., 0.], [40., 0.], [0., 40.]]), &[0, 1, 2]);
+        let image = renderer.image();
+
+        // World y points up, image rows run down
+        assert_eq!(image.get_pixel(5, 94), Some(GREEN));
+        assert_eq!(image.get_pixel(5, 5), Some(BLACK));
+        assert_eq!(image.get_pixel(35, 65), Some(BLACK));
+
+        let mut renderer = self::renderer();
+        renderer.draw_polygon(&triangle([[0., 0.], [0., 40.], [40., 0.]]), &[0, 1, 2]);
+
+        assert_eq!(renderer.image().get_pixel(5, 94), Some(BLACK));
+    }
+
+    #[test]
+    fn png_round_trips() {
+        let mut renderer = renderer();
+        renderer.draw_circles(&[RawInstance::new([50., 50.], 20.)]);
+        let image = renderer.into_image();
+
+        let png = image.to_png().unwrap();
+        let decoder = png::Decoder::new(png.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut pixels).unwrap();
+
+        assert_eq!(pixels, image.pixels);
+    }
+}
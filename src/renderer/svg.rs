@@ -0,0 +1,221 @@
+//====================================================================
+
+use std::{fmt::Write, path::Path};
+
+use anyhow::Context;
+use glam::{Vec2, Vec3Swizzles};
+
+use super::{circles::RawInstance, software::to_srgba8, uniques::OrthographicCamera};
+
+//====================================================================
+
+/// Builds an SVG document from the same data the renderer draws. Coordinates stay
+/// in world space with y pointing up, the document flips them for SVG.
+#[derive(Clone, Debug)]
+pub struct SvgDocument {
+    elements: Vec<String>,
+    bounds: Option<(Vec2, Vec2)>,
+    view: Option<(Vec2, Vec2)>,
+    background: Option<[f32; 4]>,
+    padding: f32,
+}
+
+impl Default for SvgDocument {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SvgDocument {
+    pub fn new() -> Self {
+        Self {
+            elements: Vec::new(),
+            bounds: None,
+            view: None,
+            background: None,
+            padding: 10.,
+        }
+    }
+
+    /// Use a fixed view instead of fitting the view around everything drawn.
+    pub fn with_view(mut self, min: Vec2, max: Vec2) -> Self {
+        self.view = Some((min.min(max), min.max(max)));
+        self
+    }
+
+    /// Use the area visible through a camera as the view. Camera rotation is ignored.
+    pub fn with_camera(self, camera: &OrthographicCamera) -> Self {
+        let offset = -camera.translation.xy();
+
+        self.with_view(
+            Vec2::new(camera.left, camera.bottom) + offset,
+            Vec2::new(camera.right, camera.top) + offset,
+        )
+    }
+
+    pub fn with_background(mut self, color: [f32; 4]) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    /// Space left around the drawing when fitting the view.
+    pub fn with_padding(mut self, padding: f32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    //--------------------------------------------------
+
+    pub fn add_circles(&mut self, instances: &[RawInstance]) {
+        instances.iter().for_each(|instance| {
+            let pos = Vec2::from(instance.pos);
+
+            if instance.color[3] != 0. {
+                self.elements.push(format!(
+                    r#"<circle cx="{}" cy="{}" r="{}" {}/>"#,
+                    round(pos.x),
+                    round(pos.y),
+                    round(instance.radius),
+                    paint("fill", instance.color),
+                ));
+            }
+
+            // Strokes are centred on the path while the shader draws borders outside the radius
+            if instance.border_radius > 0. && instance.border_color[3] != 0. {
+                self.elements.push(format!(
+                    r#"<circle cx="{}" cy="{}" r="{}" fill="none" {} stroke-width="{}"/>"#,
+                    round(pos.x),
+                    round(pos.y),
+                    round(instance.radius + instance.border_radius / 2.),
+                    paint("stroke", instance.border_color),
+                    round(instance.border_radius),
+                ));
+            }
+
+            let extent = Vec2::splat(instance.radius + instance.border_radius);
+            self.expand(pos - extent);
+            self.expand(pos + extent);
+        });
+    }
+
+    /// Add a filled body outline from the side points produced by `triangle_list`.
+    pub fn add_outline(&mut self, points: &[[f32; 2]], color: [f32; 4]) {
+        if points.len() < 4 {
+            return;
+        }
+
+        // Points alternate right and left sides, walk down one and back up the other
+        let right = points.iter().step_by(2);
+        let left = points.iter().skip(1).step_by(2).rev();
+
+        let points = right
+            .chain(left)
+            .map(|point| Vec2::from(*point))
+            .collect::<Vec<_>>();
+        self.add_polygon(&points, color);
+    }
+
+    /// Add a filled closed polygon, such as a body outline from `ik::body_outline`.
+    pub fn add_polygon(&mut self, points: &[Vec2], color: [f32; 4]) {
+        if points.len() < 3 {
+            return;
+        }
+
+        let points = points
+            .iter()
+            .map(|point| {
+                self.expand(*point);
+                format!("{},{}", round(point.x), round(point.y))
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        self.elements.push(format!(
+            r#"<polygon points="{}" {}/>"#,
+            points,
+            paint("fill", color)
+        ));
+    }
+
+    //--------------------------------------------------
+
+    fn expand(&mut self, point: Vec2) {
+        self.bounds = Some(match self.bounds {
+            Some((min, max)) => (min.min(point), max.max(point)),
+            None => (point, point),
+        });
+    }
+
+    fn view(&self) -> (Vec2, Vec2) {
+        if let Some(view) = self.view {
+            return view;
+        }
+
+        match self.bounds {
+            Some((min, max)) => (min - self.padding, max + self.padding),
+            None => (Vec2::ZERO, Vec2::ONE),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+
+        std::fs::write(path, self.to_string())
+            .with_context(|| format!("Failed to write svg to {}", path.display()))
+    }
+}
+
+impl std::fmt::Display for SvgDocument {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (min, max) = self.view();
+        let (min, max) = (min.map(round), max.map(round));
+        let size = (max - min).map(round);
+
+        writeln!(
+            f,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}" width="{}" height="{}">"#,
+            min.x, -max.y, size.x, size.y, size.x, size.y
+        )?;
+
+        if let Some(background) = self.background {
+            writeln!(
+                f,
+                r#"  <rect x="{}" y="{}" width="{}" height="{}" {}/>"#,
+                min.x,
+                -max.y,
+                size.x,
+                size.y,
+                paint("fill", background)
+            )?;
+        }
+
+        writeln!(f, r#"  <g transform="scale(1,-1)">"#)?;
+        self.elements
+            .iter()
+            .try_for_each(|element| writeln!(f, "    {}", element))?;
+        writeln!(f, "  </g>")?;
+
+        f.write_str("</svg>\n")
+    }
+}
+
+//====================================================================
+
+/// Keep coordinates to two decimals, avoiding float noise and negative zeros.
+fn round(value: f32) -> f32 {
+    (value * 100.).round() / 100. + 0.
+}
+
+/// Fill or stroke attributes for a linear colour.
+fn paint(attribute: &str, color: [f32; 4]) -> String {
+    let [r, g, b, a] = to_srgba8(color);
+
+    let mut paint = format!(r##"{}="#{:02x}{:02x}{:02x}""##, attribute, r, g, b);
+    if a != 255 {
+        write!(paint, r#" {}-opacity="{}""#, attribute, color[3]).unwrap();
+    }
+
+    paint
+}
+
+//====================================================================
@@ -5,7 +5,15 @@ use glyphon::{
     TextAtlas, TextBounds, TextRenderer, Viewport,
 };
 
-use super::tools::{Pipeline, PipelineUpdate};
+use glam::{vec4, Vec2};
+
+use super::{
+    tools::{Pipeline, PipelineUpdate},
+    uniques::Camera,
+};
+
+/// Line height as a multiple of the font size.
+const LINE_HEIGHT: f32 = 1.4;
 
 //====================================================================
 
@@ -15,6 +23,7 @@ pub struct TextPipeline {
     swash_cache: SwashCache,
     atlas: TextAtlas,
     viewport: Viewport,
+    resolution: Resolution,
 
     default_buffer: Buffer,
     buffers: Vec<Buffer>,
@@ -29,16 +38,16 @@ impl Pipeline for TextPipeline {
         let mut font_system = FontSystem::new();
         let swash_cache = SwashCache::new();
         let mut atlas = TextAtlas::new(core.device(), core.queue(), &cache, core.config.format);
-        let viewport = Viewport::new(core.device(), &cache);
+        let mut viewport = Viewport::new(core.device(), &cache);
+        let resolution = Resolution {
+            width: core.config().width,
+            height: core.config().height,
+        };
+        viewport.update(core.queue(), resolution);
 
-        let renderer = TextRenderer::new(
-            &mut atlas,
-            core.device(),
-            wgpu::MultisampleState::default(),
-            None,
-        );
+        let renderer = TextRenderer::new(&mut atlas, core.device(), core.multisample(), None);
 
-        let text_buffer = Buffer::new(&mut font_system, Metrics::new(30., 42.));
+        let text_buffer = Buffer::new(&mut font_system, Metrics::new(30., 30. * LINE_HEIGHT));
 
         Self {
             renderer,
@@ -46,14 +55,15 @@ impl Pipeline for TextPipeline {
             swash_cache,
             atlas,
             viewport,
+            resolution,
             default_buffer: text_buffer,
             buffers: vec![],
         }
     }
 
     fn resize(&mut self, core: &super::Core, width: u32, height: u32) {
-        self.viewport
-            .update(core.queue(), Resolution { width, height });
+        self.resolution = Resolution { width, height };
+        self.viewport.update(core.queue(), self.resolution);
     }
 
     fn render<'pass>(
@@ -71,12 +81,119 @@ impl TextPipeline {
     pub fn trim(&mut self) {
         self.atlas.trim();
     }
+
+    /// Place world space labels on screen through a camera, to be drawn alongside
+    /// any other [`TextData`].
+    pub fn project_labels(&self, camera: &dyn Camera, labels: &[TextLabel]) -> Vec<TextData> {
+        let view_projection = camera.into_uniform().view_projection();
+        let size = Vec2::new(self.resolution.width as f32, self.resolution.height as f32);
+
+        labels
+            .iter()
+            .map(|label| {
+                let clip = view_projection * vec4(label.pos.x, label.pos.y, 0., 1.);
+                let ndc = Vec2::new(clip.x, clip.y) / clip.w;
+                let screen = Vec2::new(ndc.x + 1., 1. - ndc.y) / 2. * size;
+
+                // Centre the first line vertically on the label position
+                let top = screen.y - label.size * LINE_HEIGHT / 2.;
+
+                TextData::new(label.text.clone(), (screen.x, top))
+                    .with_size(label.size)
+                    .with_color(label.color)
+                    .with_align(label.align)
+            })
+            .collect()
+    }
 }
 
+//====================================================================
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+impl TextAlign {
+    #[inline]
+    fn offset(&self, width: f32) -> f32 {
+        match self {
+            TextAlign::Left => 0.,
+            TextAlign::Center => width / 2.,
+            TextAlign::Right => width,
+        }
+    }
+}
+
+/// Text placed in screen pixels. `pos` is the top of the text, horizontally at
+/// the side given by `align`. Bounds are fitted around the laid out text.
 pub struct TextData {
     pub text: String,
     pub pos: (f32, f32),
     pub color: [u8; 3],
+    pub size: f32,
+    pub align: TextAlign,
+}
+
+impl TextData {
+    pub fn new(text: impl Into<String>, pos: (f32, f32)) -> Self {
+        Self {
+            text: text.into(),
+            pos,
+            color: [0, 0, 0],
+            size: 30.,
+            align: TextAlign::Left,
+        }
+    }
+    pub fn with_color(mut self, color: [u8; 3]) -> Self {
+        self.color = color;
+        self
+    }
+    pub fn with_size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+    pub fn with_align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+}
+
+/// Text attached to a world position that follows the camera. The label is
+/// vertically centred on `pos` and aligned horizontally around it.
+pub struct TextLabel {
+    pub text: String,
+    pub pos: Vec2,
+    pub color: [u8; 3],
+    pub size: f32,
+    pub align: TextAlign,
+}
+
+impl TextLabel {
+    pub fn new(text: impl Into<String>, pos: Vec2) -> Self {
+        Self {
+            text: text.into(),
+            pos,
+            color: [0, 0, 0],
+            size: 16.,
+            align: TextAlign::Center,
+        }
+    }
+    pub fn with_color(mut self, color: [u8; 3]) -> Self {
+        self.color = color;
+        self
+    }
+    pub fn with_size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+    pub fn with_align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
 }
 
 impl PipelineUpdate<&[TextData]> for TextPipeline {
@@ -96,6 +213,11 @@ impl PipelineUpdate<&[TextData]> for TextPipeline {
             .iter()
             .zip(self.buffers.iter_mut())
             .map(|(val, buffer)| {
+                buffer.set_metrics(
+                    &mut self.font_system,
+                    Metrics::new(val.size, val.size * LINE_HEIGHT),
+                );
+                buffer.set_size(&mut self.font_system, None, None);
                 buffer.set_text(
                     &mut self.font_system,
                     &val.text,
@@ -103,17 +225,30 @@ impl PipelineUpdate<&[TextData]> for TextPipeline {
                     Attrs::new(),
                     Shaping::Advanced,
                 );
+                buffer.shape_until_scroll(&mut self.font_system, false);
+
+                // Fit the bounds around the laid out lines
+                let (width, height) =
+                    buffer.layout_runs().fold((0., 0.), |(width, height), run| {
+                        (
+                            f32::max(width, run.line_w),
+                            f32::max(height, run.line_top + run.line_height),
+                        )
+                    });
+
+                let left = val.pos.0 - val.align.offset(width);
+                let top = val.pos.1;
 
                 TextArea {
                     buffer,
-                    left: val.pos.0,
-                    top: val.pos.1,
+                    left,
+                    top,
                     scale: 1.,
                     bounds: TextBounds {
-                        left: 0,
-                        top: 0,
-                        right: 600,
-                        bottom: 160,
+                        left: left.floor() as i32,
+                        top: top.floor() as i32,
+                        right: (left + width).ceil() as i32,
+                        bottom: (top + height).ceil() as i32,
                     },
                     default_color: Color::rgb(val.color[0], val.color[1], val.color[2]),
                 }
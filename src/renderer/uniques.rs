@@ -103,6 +103,11 @@ impl CameraUniform {
             _padding: 0,
         }
     }
+
+    #[inline]
+    pub fn view_projection(&self) -> glam::Mat4 {
+        glam::Mat4::from_cols_array(&self.view_projection)
+    }
 }
 
 pub trait Camera {
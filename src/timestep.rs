@@ -0,0 +1,93 @@
+//====================================================================
+
+use std::time::Instant;
+
+//====================================================================
+
+/// Runs a simulation at a fixed rate independent of the frame rate. Frame time
+/// is collected in an accumulator and spent in whole steps, with the remainder
+/// available as an interpolation factor for rendering between the last two steps.
+#[derive(Clone, Debug)]
+pub struct FixedTimestep {
+    step: f32,
+    accumulator: f32,
+    /// Most steps run in a single frame. Time beyond that is dropped so a slow
+    /// frame can't snowball into ever more steps.
+    max_steps: u32,
+    last: Option<Instant>,
+}
+
+impl Default for FixedTimestep {
+    fn default() -> Self {
+        Self::from_hz(60.)
+    }
+}
+
+impl FixedTimestep {
+    pub fn new(step: f32) -> Self {
+        Self {
+            step: step.max(f32::EPSILON),
+            accumulator: 0.,
+            max_steps: 8,
+            last: None,
+        }
+    }
+
+    #[inline]
+    pub fn from_hz(hz: f32) -> Self {
+        Self::new(1. / hz)
+    }
+
+    pub fn with_max_steps(mut self, max_steps: u32) -> Self {
+        self.max_steps = max_steps.max(1);
+        self
+    }
+
+    /// Length of a single step in seconds.
+    #[inline]
+    pub fn step(&self) -> f32 {
+        self.step
+    }
+
+    /// How far between the previous and the latest step the current frame lies,
+    /// from 0 to 1.
+    #[inline]
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / self.step).clamp(0., 1.)
+    }
+
+    /// Add elapsed time and return how many steps should run.
+    pub fn advance(&mut self, dt: f32) -> u32 {
+        self.accumulator += dt.max(0.);
+
+        let steps = (self.accumulator / self.step).floor() as u32;
+        if steps > self.max_steps {
+            self.accumulator = 0.;
+            return self.max_steps;
+        }
+
+        self.accumulator -= steps as f32 * self.step;
+        steps
+    }
+
+    /// Advance using the wall clock time since the previous call. The first call
+    /// starts the clock and runs no steps.
+    pub fn tick(&mut self) -> u32 {
+        let now = Instant::now();
+        let dt = self
+            .last
+            .map(|last| now.duration_since(last).as_secs_f32())
+            .unwrap_or(0.);
+        self.last = Some(now);
+
+        self.advance(dt)
+    }
+
+    /// Clear accumulated time and restart the clock.
+    pub fn reset(&mut self) {
+        self.accumulator = 0.;
+        self.last = None;
+    }
+}
+
+//====================================================================
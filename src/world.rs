@@ -0,0 +1,360 @@
+//====================================================================
+
+use glam::{vec2, Vec2};
+
+use crate::renderer::debug::DebugDraw;
+
+//====================================================================
+
+const DEBUG_COLOR: [f32; 4] = [0.6, 0.6, 0.6, 1.];
+const DEBUG_THICKNESS: f32 = 2.;
+/// Distance a target may sit inside a surface and still count as on it.
+const OCCLUSION_TOLERANCE: f32 = 0.001;
+
+//====================================================================
+
+/// Ground line sampled at even spacing. Everything below the line is solid.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Heightfield {
+    /// Horizontal position of the first sample.
+    pub start: f32,
+    pub spacing: f32,
+    pub heights: Vec<f32>,
+}
+
+impl Heightfield {
+    pub fn new(start: f32, spacing: f32, heights: Vec<f32>) -> Self {
+        Self {
+            start,
+            spacing: spacing.max(f32::EPSILON),
+            heights,
+        }
+    }
+
+    /// Ground height at `x`, or `None` outside the sampled range.
+    pub fn height(&self, x: f32) -> Option<f32> {
+        let offset = (x - self.start) / self.spacing;
+        if offset < 0. || self.heights.is_empty() {
+            return None;
+        }
+
+        let index = offset.floor() as usize;
+        match (self.heights.get(index), self.heights.get(index + 1)) {
+            (Some(a), Some(b)) => Some(a + (b - a) * offset.fract()),
+            (Some(a), None) if offset.fract() == 0. => Some(*a),
+            _ => None,
+        }
+    }
+
+    pub fn points(&self) -> Vec<Vec2> {
+        self.heights
+            .iter()
+            .enumerate()
+            .map(|(index, height)| vec2(self.start + index as f32 * self.spacing, *height))
+            .collect()
+    }
+}
+
+/// Static piece of world geometry. Segments are thin walls, polygons, circles and
+/// the area below a heightfield are solid.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape {
+    Segment { start: Vec2, end: Vec2 },
+    Polygon(Vec<Vec2>),
+    Circle { center: Vec2, radius: f32 },
+    Heightfield(Heightfield),
+}
+
+impl Shape {
+    /// Closest point on the shape's boundary and whether `pos` lies inside it.
+    fn closest(&self, pos: Vec2) -> Option<(Vec2, bool)> {
+        match self {
+            Shape::Segment { start, end } => Some((closest_on_segment(pos, *start, *end), false)),
+            Shape::Polygon(points) => {
+                let closest = closest_on_polyline(pos, points.iter().chain(points.first()))?;
+                Some((closest, polygon_contains(points, pos)))
+            }
+            Shape::Circle { center, radius } => {
+                let direction = (pos - *center).normalize_or(Vec2::Y);
+                Some((
+                    *center + direction * *radius,
+                    pos.distance_squared(*center) < radius * radius,
+                ))
+            }
+            Shape::Heightfield(heightfield) => {
+                let points = heightfield.points();
+                let closest = closest_on_polyline(pos, points.iter())?;
+                let inside = heightfield
+                    .height(pos.x)
+                    .is_some_and(|height| pos.y < height);
+
+                Some((closest, inside))
+            }
+        }
+    }
+
+    fn contains(&self, pos: Vec2) -> bool {
+        self.closest(pos).is_some_and(|(_, inside)| inside)
+    }
+
+    /// Nearest hit along a ray with a normalized direction.
+    fn raycast(&self, origin: Vec2, direction: Vec2, max_distance: f32) -> Option<(f32, Vec2)> {
+        let edges = |points: &[Vec2], closed: bool| {
+            let mut edges = points
+                .windows(2)
+                .map(|pair| (pair[0], pair[1]))
+                .collect::<Vec<_>>();
+            if closed && points.len() > 2 {
+                edges.push((points[points.len() - 1], points[0]));
+            }
+            edges
+        };
+
+        let nearest = |edges: Vec<(Vec2, Vec2)>| {
+            edges
+                .into_iter()
+                .filter_map(|(start, end)| ray_segment(origin, direction, start, end))
+                .filter(|(distance, _)| *distance <= max_distance)
+                .min_by(|a, b| a.0.total_cmp(&b.0))
+        };
+
+        match self {
+            Shape::Segment { start, end } => nearest(vec![(*start, *end)]),
+            Shape::Polygon(points) => nearest(edges(points, true)),
+            Shape::Heightfield(heightfield) => nearest(edges(&heightfield.points(), false)),
+            Shape::Circle { center, radius } => {
+                let offset = origin - *center;
+                let along = offset.dot(direction);
+                let discriminant = along * along - (offset.length_squared() - radius * radius);
+                if discriminant < 0. {
+                    return None;
+                }
+
+                // Rays starting inside hit the far side from within
+                let root = discriminant.sqrt();
+                let (distance, facing) = match -along - root >= 0. {
+                    true => (-along - root, 1.),
+                    false => (-along + root, -1.),
+                };
+
+                (distance >= 0. && distance <= max_distance).then(|| {
+                    let point = origin + direction * distance;
+                    (
+                        distance,
+                        (point - *center).normalize_or(-direction) * facing,
+                    )
+                })
+            }
+        }
+    }
+
+    fn debug_draw(&self, draw: &mut DebugDraw) {
+        let polyline = |draw: &mut DebugDraw, points: &[Vec2]| {
+            points.windows(2).for_each(|pair| {
+                draw.line(pair[0], pair[1], DEBUG_THICKNESS, DEBUG_COLOR);
+            });
+        };
+
+        match self {
+            Shape::Segment { start, end } => {
+                draw.line(*start, *end, DEBUG_THICKNESS, DEBUG_COLOR);
+            }
+            Shape::Polygon(points) => {
+                polyline(draw, points);
+                if let (Some(first), Some(last)) = (points.first(), points.last()) {
+                    draw.line(*last, *first, DEBUG_THICKNESS, DEBUG_COLOR);
+                }
+            }
+            Shape::Circle { center, radius } => {
+                draw.circle(*center, *radius, DEBUG_THICKNESS, DEBUG_COLOR);
+            }
+            Shape::Heightfield(heightfield) => polyline(draw, &heightfield.points()),
+        }
+    }
+}
+
+//====================================================================
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RayHit {
+    pub point: Vec2,
+    /// Surface normal facing back along the ray.
+    pub normal: Vec2,
+    pub distance: f32,
+    /// Index of the shape that was hit.
+    pub shape: usize,
+}
+
+/// Static obstacles and terrain that chains and locomotion can query.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct World {
+    pub shapes: Vec<Shape>,
+}
+
+impl World {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_shape(mut self, shape: Shape) -> Self {
+        self.add_shape(shape);
+        self
+    }
+
+    pub fn add_shape(&mut self, shape: Shape) -> usize {
+        self.shapes.push(shape);
+        self.shapes.len() - 1
+    }
+
+    /// Nearest hit along a ray, ignoring anything further than `max_distance`.
+    pub fn raycast(&self, origin: Vec2, direction: Vec2, max_distance: f32) -> Option<RayHit> {
+        let direction = direction.try_normalize()?;
+
+        self.shapes
+            .iter()
+            .enumerate()
+            .filter_map(|(index, shape)| {
+                let (distance, normal) = shape.raycast(origin, direction, max_distance)?;
+                Some(RayHit {
+                    point: origin + direction * distance,
+                    normal,
+                    distance,
+                    shape: index,
+                })
+            })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+    }
+
+    /// First surface straight below a point, searching from `reach` above it, e.g.
+    /// to plant a foot target on the ground.
+    pub fn ground(&self, pos: Vec2, reach: f32) -> Option<RayHit> {
+        self.raycast(pos + Vec2::Y * reach, -Vec2::Y, f32::INFINITY)
+    }
+
+    /// Whether a point lies inside solid geometry.
+    pub fn contains(&self, pos: Vec2) -> bool {
+        self.shapes.iter().any(|shape| shape.contains(pos))
+    }
+
+    /// Whether the straight line between two points crosses geometry, or `to` is
+    /// buried inside it. Points resting on a surface, e.g. placed with
+    /// [`World::ground`], count as clear.
+    pub fn occluded(&self, from: Vec2, to: Vec2) -> bool {
+        let distance = from.distance(to);
+        let nudged = to + (from - to).normalize_or_zero() * OCCLUSION_TOLERANCE;
+
+        self.contains(nudged)
+            || self
+                .raycast(from, to - from, distance)
+                .is_some_and(|hit| hit.distance < distance - OCCLUSION_TOLERANCE)
+    }
+
+    /// Move a circle out of every shape it overlaps. Returns the new position and
+    /// whether it had to move.
+    pub fn push_out(&self, pos: Vec2, radius: f32) -> (Vec2, bool) {
+        self.shapes
+            .iter()
+            .fold((pos, false), |(pos, moved), shape| {
+                let Some((closest, inside)) = shape.closest(pos) else {
+                    return (pos, moved);
+                };
+
+                let offset = closest - pos;
+                let distance = offset.length();
+
+                let pushed = match inside {
+                    true => pos + offset.normalize_or(Vec2::Y) * (distance + radius),
+                    false if distance < radius => {
+                        let normal = match distance > f32::EPSILON {
+                            true => -offset / distance,
+                            false => Vec2::Y,
+                        };
+                        closest + normal * radius
+                    }
+                    false => return (pos, moved),
+                };
+
+                (pushed, true)
+            })
+    }
+
+    pub fn debug_draw(&self, draw: &mut DebugDraw) {
+        self.shapes.iter().for_each(|shape| shape.debug_draw(draw));
+    }
+}
+
+//====================================================================
+
+fn closest_on_segment(pos: Vec2, start: Vec2, end: Vec2) -> Vec2 {
+    let edge = end - start;
+    let length = edge.length_squared();
+    if length <= f32::EPSILON {
+        return start;
+    }
+
+    start + edge * ((pos - start).dot(edge) / length).clamp(0., 1.)
+}
+
+fn closest_on_polyline<'a>(pos: Vec2, points: impl Iterator<Item = &'a Vec2>) -> Option<Vec2> {
+    let points = points.copied().collect::<Vec<_>>();
+
+    match points.as_slice() {
+        [] => None,
+        [point] => Some(*point),
+        points => points
+            .windows(2)
+            .map(|pair| closest_on_segment(pos, pair[0], pair[1]))
+            .min_by(|a, b| a.distance_squared(pos).total_cmp(&b.distance_squared(pos))),
+    }
+}
+
+/// Even-odd rule, so the winding of the polygon doesn't matter.
+fn polygon_contains(points: &[Vec2], pos: Vec2) -> bool {
+    if points.len() < 3 {
+        return false;
+    }
+
+    let mut inside = false;
+    let mut previous = points[points.len() - 1];
+
+    points.iter().for_each(|point| {
+        if (point.y > pos.y) != (previous.y > pos.y)
+            && pos.x < point.x + (previous.x - point.x) * (pos.y - point.y) / (previous.y - point.y)
+        {
+            inside = !inside;
+        }
+        previous = *point;
+    });
+
+    inside
+}
+
+/// Distance along the ray and surface normal facing the ray's origin.
+fn ray_segment(origin: Vec2, direction: Vec2, start: Vec2, end: Vec2) -> Option<(f32, Vec2)> {
+    let edge = end - start;
+    let denominator = direction.perp_dot(edge);
+    if denominator.abs() <= f32::EPSILON {
+        return None;
+    }
+
+    let offset = start - origin;
+    let distance = offset.perp_dot(edge) / denominator;
+    let along = offset.perp_dot(direction) / denominator;
+
+    if distance < 0. || !(0. ..=1.).contains(&along) {
+        return None;
+    }
+
+    let normal = edge.perp().normalize_or_zero();
+    let normal = match normal.dot(direction) > 0. {
+        true => -normal,
+        false => normal,
+    };
+
+    Some((distance, normal))
+}
+
+//====================================================================